@@ -1,121 +1,339 @@
-use crate::converter::DnDStatBlock;
-use crate::database::{Database, Trainer, UserPokemon};
-use crate::pokemon::{PokeApiPokemonResponse, PokeApiResource, PokeApiResponse, Pokemon};
-use tauri::State;
+use crate::converter::{
+    ability_modifier, ConversionProfile, DamageType, DnDStatBlock, HitPointMethod,
+};
+use crate::database::{
+    Database, DatabaseStatus, PokemonBox, PokemonQuery, StorageLocation, Trainer, UserPokemon,
+};
+use crate::loader::{self, FailedLoad, LoadCancellation, LoadReport, LoadToken, LoaderOptions};
+use crate::pokemon::{
+    DexEntry, EvolutionChain, EvolutionNode, Generation, Move, PokeApiEvolutionChainResponse,
+    PokeApiGenerationResponse, PokeApiMoveResponse, PokeApiPokedexResponse, PokeApiPokemonResponse,
+    PokeApiResource, PokeApiResponse, PokeApiSpeciesResponse, PokeApiTypeResponse, Pokedex,
+    Pokemon, PokemonCustomization, Species, MAX_LEVEL,
+};
+use crate::source::{ApiDataSource, PokemonSource, SharedSource, SourceError};
+use crate::type_chart::TypeChart;
+use std::collections::HashSet;
+use std::path::Path;
+use tauri::{AppHandle, Manager, State};
 
-const POKEAPI_BASE_URL: &str = "https://pokeapi.co/api/v2";
+// Emitted with a LoadProgress as each Pokemon in a bulk load finishes
+const LOAD_PROGRESS_EVENT: &str = "pokemon-load-progress";
+
+// Page size for the /generation and /pokedex lists, which comfortably fit in one
+const METADATA_LIST_LIMIT: u32 = 1000;
 
 // Pokemon data commands with caching
 #[tauri::command]
-pub async fn fetch_pokemon(db: State<'_, Database>, id: u32) -> Result<Pokemon, String> {
-    // First, check if we have a valid cached version
-    match db.is_pokemon_cache_valid(id, 24).await {
-        // Cache valid for 24 hours
-        Ok(is_valid) if is_valid => {
-            if let Ok(Some(cached_pokemon)) = db.get_cached_pokemon(id).await {
-                return Ok(cached_pokemon);
-            }
+pub async fn fetch_pokemon(
+    db: State<'_, Database>,
+    source: State<'_, SharedSource>,
+    id: u32,
+) -> Result<Pokemon, String> {
+    load_pokemon(&db, source.as_ref(), id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+async fn load_pokemon(
+    db: &Database,
+    source: &dyn PokemonSource,
+    id: u32,
+) -> Result<Pokemon, SourceError> {
+    // Cached copies are used as they are for 24 hours, or indefinitely if imported
+    let cached = db.get_cached_pokemon(id).await.ok().flatten();
+    if let Some(pokemon) = &cached {
+        if db.is_pokemon_cache_valid(id, 24).await.unwrap_or(false) {
+            return Ok(pokemon.clone());
         }
-        _ => {} // Continue to API fetch if cache check fails
     }
 
-    // Cache miss or invalid, fetch from API
-    let url = format!("{}/pokemon/{}", POKEAPI_BASE_URL, id);
+    // Cache miss or stale: refresh from the configured source, but keep working from a
+    // stale copy if the source cannot be reached
+    match download_pokemon(db, source, id).await {
+        Ok(pokemon) => Ok(pokemon),
+        Err(e) => match cached {
+            Some(stale) => {
+                eprintln!("Using cached Pokemon {} after refresh failed: {}", id, e);
+                Ok(stale)
+            }
+            None => Err(e),
+        },
+    }
+}
 
-    match reqwest::get(&url).await {
-        Ok(response) => {
-            if response.status().is_success() {
-                match response.json::<PokeApiPokemonResponse>().await {
-                    Ok(api_response) => {
-                        let pokemon = Pokemon::from_pokeapi_response(api_response);
+// Fetch a Pokemon with its moves and species from the source and cache them all.
+// Nothing is cached unless every part arrived, so a failed move or species fetch is
+// returned for the caller (or the bulk loader) to retry rather than leaving a partial
+// Pokemon cached for good.
+async fn download_pokemon(
+    db: &Database,
+    source: &dyn PokemonSource,
+    id: u32,
+) -> Result<Pokemon, SourceError> {
+    let api_response = source
+        .get::<PokeApiPokemonResponse>(&format!("pokemon/{}", id))
+        .await
+        .inspect_err(|e| eprintln!("Failed to fetch Pokemon {}: {}", id, e))?;
 
-                        // Cache the fetched Pokemon (ignore errors)
-                        let _ = db.cache_pokemon(&pokemon).await;
+    let moves = resolve_moves(db, source, &api_response.level_up_moves()).await?;
+    let species_name = api_response.species.name.clone();
+    let species_response = fetch_species_response(source, &species_name)
+        .await
+        .inspect_err(|e| eprintln!("Failed to fetch species {}: {}", species_name, e))?;
+    let pokemon = Pokemon::from_pokeapi_response(api_response, moves);
 
-                        Ok(pokemon)
-                    }
-                    Err(e) => {
-                        eprintln!("Failed to parse PokeAPI response: {}", e);
-                        Err(format!("Failed to parse Pokemon data: {}", e))
-                    }
-                }
-            } else {
-                Err(format!("PokeAPI returned status: {}", response.status()))
-            }
-        }
-        Err(e) => {
-            eprintln!("Failed to fetch from PokeAPI: {}", e);
-            Err(format!("Failed to fetch Pokemon: {}", e))
+    // Cache the fetched Pokemon and its species (ignore errors)
+    if db.cache_pokemon(&pokemon).await.is_ok() {
+        let species = Species::from_pokeapi_response(&species_response);
+        store_species(db, pokemon.id, &species).await;
+    }
+
+    Ok(pokemon)
+}
+
+// Species record by National Dex number, from the cache where possible
+async fn fetch_species(
+    db: &Database,
+    source: &dyn PokemonSource,
+    id: u32,
+) -> Result<Species, String> {
+    if let Ok(Some(species)) = db.get_cached_species(id).await {
+        return Ok(species);
+    }
+
+    let response = fetch_species_response(source, &id.to_string())
+        .await
+        .map_err(|e| e.to_string())?;
+    let species = Species::from_pokeapi_response(&response);
+    store_species(db, id, &species).await;
+
+    Ok(species)
+}
+
+// Cache a species and index its flavor text for the Pokemon (ignore errors)
+async fn store_species(db: &Database, pokemon_id: u32, species: &Species) {
+    let _ = db.cache_species(species).await;
+    if let Some(flavor_text) = &species.flavor_text {
+        let _ = db.set_pokemon_flavor_text(pokemon_id, flavor_text).await;
+    }
+}
+
+#[tauri::command]
+pub async fn get_species(
+    db: State<'_, Database>,
+    source: State<'_, SharedSource>,
+    pokemon_id: u32,
+) -> Result<Species, String> {
+    fetch_species(&db, source.as_ref(), pokemon_id).await
+}
+
+async fn fetch_species_response(
+    source: &dyn PokemonSource,
+    name_or_id: &str,
+) -> Result<PokeApiSpeciesResponse, SourceError> {
+    source.get(&format!("pokemon-species/{}", name_or_id)).await
+}
+
+async fn fetch_evolution_chain(
+    db: &Database,
+    source: &dyn PokemonSource,
+    id: u32,
+) -> Result<EvolutionChain, String> {
+    if let Ok(Some(chain)) = db.get_cached_evolution_chain(id).await {
+        return Ok(chain);
+    }
+
+    let chain = source
+        .get::<PokeApiEvolutionChainResponse>(&format!("evolution-chain/{}", id))
+        .await
+        .map(|response| EvolutionChain::from_pokeapi_response(&response))
+        .map_err(|e| e.to_string())?;
+
+    // Cache the fetched chain (ignore errors)
+    let _ = db.cache_evolution_chain(&chain).await;
+
+    Ok(chain)
+}
+
+// Direct evolutions of a species; none if it has no chain
+async fn fetch_evolutions(
+    db: &Database,
+    source: &dyn PokemonSource,
+    species: &Species,
+) -> Result<Vec<EvolutionNode>, String> {
+    match species.evolution_chain_id {
+        Some(chain_id) => Ok(fetch_evolution_chain(db, source, chain_id)
+            .await?
+            .evolutions_from(&species.name)),
+        None => Ok(Vec::new()),
+    }
+}
+
+// Whole evolution family of a Pokemon, as a tree from the base form
+#[tauri::command]
+pub async fn get_evolution_chain(
+    db: State<'_, Database>,
+    source: State<'_, SharedSource>,
+    pokemon_id: u32,
+) -> Result<Option<EvolutionChain>, String> {
+    let species = fetch_species(&db, source.as_ref(), pokemon_id).await?;
+
+    match species.evolution_chain_id {
+        Some(chain_id) => fetch_evolution_chain(&db, source.as_ref(), chain_id)
+            .await
+            .map(Some),
+        None => Ok(None),
+    }
+}
+
+// Resolve a learnset against the /move endpoint, using the move cache where possible.
+// Fails on the first move that cannot be fetched, since a partial moveset would be
+// cached as if complete.
+async fn resolve_moves(
+    db: &Database,
+    source: &dyn PokemonSource,
+    learnset: &[(String, u32)],
+) -> Result<Vec<Move>, SourceError> {
+    let mut moves = Vec::new();
+
+    for (name, level) in learnset {
+        if let Ok(Some(mut cached_move)) = db.get_cached_move(name).await {
+            cached_move.level_learned_at = *level;
+            moves.push(cached_move);
+            continue;
         }
+
+        let response = source
+            .get::<PokeApiMoveResponse>(&format!("move/{}", name))
+            .await
+            .inspect_err(|e| eprintln!("Failed to resolve move {}: {}", name, e))?;
+        let pokemon_move = Move::from_pokeapi_response(response, *level);
+
+        // Cache the fetched move (ignore errors)
+        let _ = db.cache_move(&pokemon_move).await;
+
+        moves.push(pokemon_move);
     }
+
+    Ok(moves)
 }
 
 #[tauri::command]
 pub async fn search_pokemon(
     db: State<'_, Database>,
+    source: State<'_, SharedSource>,
     query: String,
 ) -> Result<Vec<Pokemon>, String> {
     // If query is a number, fetch that specific Pokemon
     if let Ok(id) = query.parse::<u32>() {
-        match fetch_pokemon(db.clone(), id).await {
+        match load_pokemon(&db, source.as_ref(), id).await {
             Ok(pokemon) => return Ok(vec![pokemon]),
             Err(_) => return Ok(vec![]), // Pokemon not found
         }
     }
 
-    // Otherwise, search by name using the species endpoint
-    let url = format!("{}/pokemon-species?limit=1000", POKEAPI_BASE_URL);
-
-    match reqwest::get(&url).await {
-        Ok(response) => {
-            if response.status().is_success() {
-                match response.json::<PokeApiResponse<PokeApiResource>>().await {
-                    Ok(api_response) => {
-                        // Filter species that match the query
-                        let matching_species: Vec<_> = api_response
-                            .results
-                            .into_iter()
-                            .filter(|species| {
-                                species.name.to_lowercase().contains(&query.to_lowercase())
-                            })
-                            .take(20) // Limit to 20 results
-                            .collect();
-
-                        // Fetch full Pokemon data for each matching species
-                        let mut results = Vec::new();
-                        for species in matching_species {
-                            // Extract ID from species URL
-                            if let Some(id_str) = species.url.split('/').nth_back(1) {
-                                if let Ok(id) = id_str.parse::<u32>() {
-                                    if let Ok(pokemon) = fetch_pokemon(db.clone(), id).await {
-                                        results.push(pokemon);
-                                    }
-                                }
-                            }
-                        }
+    // Otherwise, search the local full-text index
+    db.search_pokemon(&query, 20)
+        .await
+        .map_err(|e| format!("Failed to search Pokemon: {}", e))
+}
 
-                        Ok(results)
-                    }
-                    Err(e) => {
-                        eprintln!("Failed to parse species search response: {}", e);
-                        Err(format!("Failed to search Pokemon: {}", e))
-                    }
-                }
-            } else {
-                Err(format!("PokeAPI returned status: {}", response.status()))
-            }
-        }
-        Err(e) => {
-            eprintln!("Failed to search PokeAPI: {}", e);
-            Err(format!("Failed to search Pokemon: {}", e))
-        }
+// Stat conversion commands
+#[tauri::command]
+pub async fn convert_pokemon_to_dnd(
+    db: State<'_, Database>,
+    pokemon: Pokemon,
+    level: u8,
+    profile_name: Option<String>,
+    hit_point_method: Option<HitPointMethod>,
+) -> Result<DnDStatBlock, String> {
+    let profile = resolve_profile(&db, profile_name, hit_point_method).await?;
+    let type_chart = db.get_type_chart().await.map_err(|e| e.to_string())?;
+    Ok(DnDStatBlock::from_pokemon(
+        &pokemon,
+        level,
+        &profile,
+        &type_chart,
+    ))
+}
+
+// Stat block for one of the trainer's own Pokemon, using its level and customization
+#[tauri::command]
+pub async fn convert_user_pokemon_to_dnd(
+    db: State<'_, Database>,
+    source: State<'_, SharedSource>,
+    user_pokemon_id: i64,
+    profile_name: Option<String>,
+    hit_point_method: Option<HitPointMethod>,
+) -> Result<DnDStatBlock, String> {
+    let owned = get_owned_pokemon(&db, user_pokemon_id).await?;
+    let species = load_pokemon(&db, source.as_ref(), owned.pokemon_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    let profile = resolve_profile(&db, profile_name, hit_point_method).await?;
+    let type_chart = db.get_type_chart().await.map_err(|e| e.to_string())?;
+
+    Ok(DnDStatBlock::from_individual(
+        &species,
+        owned.level,
+        &owned.customization,
+        &profile,
+        &type_chart,
+    ))
+}
+
+// Named profile (or the default), optionally overriding how its hit points are set
+async fn resolve_profile(
+    db: &Database,
+    profile_name: Option<String>,
+    hit_point_method: Option<HitPointMethod>,
+) -> Result<ConversionProfile, String> {
+    let mut profile = match profile_name {
+        Some(name) => db
+            .get_conversion_profile(&name)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Unknown conversion profile: {}", name))?,
+        None => ConversionProfile::default(),
+    };
+    // Profiles saved before validation existed may still be unusable
+    profile.validate()?;
+
+    if let Some(method) = hit_point_method {
+        profile.hit_point_method = method;
     }
+    Ok(profile)
+}
+
+#[tauri::command]
+pub async fn get_conversion_profiles(
+    db: State<'_, Database>,
+) -> Result<Vec<ConversionProfile>, String> {
+    db.get_conversion_profiles()
+        .await
+        .map_err(|e| e.to_string())
 }
 
-// Stat conversion commands
 #[tauri::command]
-pub async fn convert_pokemon_to_dnd(pokemon: Pokemon, level: u8) -> Result<DnDStatBlock, String> {
-    Ok(DnDStatBlock::from_pokemon(&pokemon, level))
+pub async fn save_conversion_profile(
+    db: State<'_, Database>,
+    profile: ConversionProfile,
+) -> Result<(), String> {
+    profile.validate()?;
+    db.save_conversion_profile(&profile)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_conversion_profile(
+    db: State<'_, Database>,
+    name: String,
+) -> Result<(), String> {
+    db.delete_conversion_profile(&name)
+        .await
+        .map_err(|e| e.to_string())
 }
 
 // Database commands
@@ -156,14 +374,172 @@ pub async fn get_trainer_pokemon(
 #[tauri::command]
 pub async fn update_pokemon_level(
     db: State<'_, Database>,
+    source: State<'_, SharedSource>,
     user_pokemon_id: i64,
     new_level: u8,
 ) -> Result<(), String> {
-    db.update_pokemon_level(user_pokemon_id, new_level)
+    let owned = get_owned_pokemon(&db, user_pokemon_id).await?;
+    let level = new_level.clamp(1, MAX_LEVEL);
+
+    // Experience already within the new level is kept; otherwise it moves to the start
+    // of that level's curve. Without the species (e.g. offline) only the level changes.
+    let experience = match fetch_species(&db, source.as_ref(), owned.pokemon_id).await {
+        Ok(species) => {
+            let growth_rate = species.growth_rate;
+            let floor = growth_rate.experience_for_level(level);
+            let within_level = owned.experience >= floor
+                && (level == MAX_LEVEL
+                    || owned.experience < growth_rate.experience_for_level(level + 1));
+            if within_level {
+                owned.experience
+            } else {
+                floor
+            }
+        }
+        Err(e) => {
+            eprintln!(
+                "Keeping experience for Pokemon {}; species unavailable: {}",
+                user_pokemon_id, e
+            );
+            owned.experience
+        }
+    };
+    db.set_pokemon_progress(user_pokemon_id, level, experience)
         .await
         .map_err(|e| e.to_string())
 }
 
+// Add experience and apply any level-ups from the species' growth rate
+#[tauri::command]
+pub async fn award_experience(
+    db: State<'_, Database>,
+    source: State<'_, SharedSource>,
+    user_pokemon_id: i64,
+    amount: u32,
+) -> Result<LevelUpReport, String> {
+    let mut owned = get_owned_pokemon(&db, user_pokemon_id).await?;
+    let species = fetch_species(&db, source.as_ref(), owned.pokemon_id).await?;
+    let growth_rate = species.growth_rate;
+
+    // Pokemon whose level was set before experience was tracked start at that level's floor
+    let previous_level = owned.level;
+    let experience = owned
+        .experience
+        .max(growth_rate.experience_for_level(previous_level))
+        .saturating_add(amount)
+        .min(growth_rate.experience_for_level(MAX_LEVEL));
+    let level = growth_rate
+        .level_for_experience(experience)
+        .max(previous_level);
+
+    let pokemon = load_pokemon(&db, source.as_ref(), owned.pokemon_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    let new_moves = pokemon
+        .moves
+        .into_iter()
+        .filter(|m| {
+            m.level_learned_at > previous_level as u32 && m.level_learned_at <= level as u32
+        })
+        .collect();
+
+    // Evolution data is a bonus; failing to fetch it does not hold back the experience
+    let available_evolutions = match fetch_evolutions(&db, source.as_ref(), &species).await {
+        Ok(evolutions) => evolutions
+            .into_iter()
+            .filter(|e| e.becomes_available(previous_level, level))
+            .collect(),
+        Err(e) => {
+            eprintln!("Failed to check evolutions for {}: {}", species.name, e);
+            Vec::new()
+        }
+    };
+
+    // Saved last, so an error above leaves the Pokemon as it was
+    db.set_pokemon_progress(user_pokemon_id, level, experience)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    owned.level = level;
+    owned.experience = experience;
+    Ok(LevelUpReport {
+        pokemon: owned,
+        previous_level,
+        experience_to_next_level: if level >= MAX_LEVEL {
+            0
+        } else {
+            growth_rate
+                .experience_for_level(level + 1)
+                .saturating_sub(experience)
+        },
+        new_moves,
+        available_evolutions,
+    })
+}
+
+// Evolve into the given species. Level requirements are enforced; items, trades,
+// friendship and the like are left to the table to adjudicate.
+#[tauri::command]
+pub async fn evolve_pokemon(
+    db: State<'_, Database>,
+    source: State<'_, SharedSource>,
+    user_pokemon_id: i64,
+    into_species_id: u32,
+) -> Result<UserPokemon, String> {
+    let mut owned = get_owned_pokemon(&db, user_pokemon_id).await?;
+    let species = fetch_species(&db, source.as_ref(), owned.pokemon_id).await?;
+
+    let evolution = fetch_evolutions(&db, source.as_ref(), &species)
+        .await?
+        .into_iter()
+        .find(|e| e.species_id == into_species_id)
+        .ok_or_else(|| {
+            format!(
+                "{} does not evolve into species {}",
+                species.name, into_species_id
+            )
+        })?;
+    let level_met = evolution.conditions.is_empty()
+        || evolution
+            .conditions
+            .iter()
+            .any(|c| c.min_level.is_none_or(|min| owned.level as u32 >= min));
+    if !level_met {
+        return Err(format!(
+            "{} evolves into {} at level {}",
+            species.name,
+            evolution.species_name,
+            evolution.min_level().unwrap_or_default()
+        ));
+    }
+
+    let current = load_pokemon(&db, source.as_ref(), owned.pokemon_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    let evolved = load_pokemon(&db, source.as_ref(), evolution.species_id)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // A chosen ability carries over to the evolution's ability in the same slot
+    let mut customization = owned.customization.clone();
+    customization.ability = customization.ability.as_ref().and_then(|name| {
+        let slot = current.abilities.iter().find(|a| &a.name == name)?.slot;
+        evolved
+            .abilities
+            .iter()
+            .find(|a| a.slot == slot)
+            .map(|a| a.name.clone())
+    });
+
+    db.evolve_pokemon(user_pokemon_id, evolved.id, &customization)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    owned.pokemon_id = evolved.id;
+    owned.customization = customization;
+    Ok(owned)
+}
+
 #[tauri::command]
 pub async fn release_pokemon(db: State<'_, Database>, user_pokemon_id: i64) -> Result<(), String> {
     db.release_pokemon(user_pokemon_id)
@@ -171,6 +547,134 @@ pub async fn release_pokemon(db: State<'_, Database>, user_pokemon_id: i64) -> R
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn update_pokemon_customization(
+    db: State<'_, Database>,
+    source: State<'_, SharedSource>,
+    user_pokemon_id: i64,
+    customization: PokemonCustomization,
+) -> Result<UserPokemon, String> {
+    let mut owned = get_owned_pokemon(&db, user_pokemon_id).await?;
+    let species = load_pokemon(&db, source.as_ref(), owned.pokemon_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    customization.validate(&species, owned.level)?;
+
+    db.update_pokemon_customization(user_pokemon_id, &customization)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    owned.customization = customization;
+    Ok(owned)
+}
+
+async fn get_owned_pokemon(db: &Database, user_pokemon_id: i64) -> Result<UserPokemon, String> {
+    db.get_user_pokemon(user_pokemon_id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Owned Pokemon {} not found", user_pokemon_id))
+}
+
+// Party and box storage commands
+#[tauri::command]
+pub async fn get_party(
+    db: State<'_, Database>,
+    trainer_id: i64,
+) -> Result<Vec<UserPokemon>, String> {
+    db.get_party(trainer_id).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_boxes(
+    db: State<'_, Database>,
+    trainer_id: i64,
+) -> Result<Vec<PokemonBox>, String> {
+    db.get_boxes(trainer_id).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_box_pokemon(
+    db: State<'_, Database>,
+    box_id: i64,
+) -> Result<Vec<UserPokemon>, String> {
+    db.get_box_pokemon(box_id).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn create_box(
+    db: State<'_, Database>,
+    trainer_id: i64,
+    name: String,
+) -> Result<i64, String> {
+    db.create_box(trainer_id, &name)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn rename_box(db: State<'_, Database>, box_id: i64, name: String) -> Result<(), String> {
+    db.rename_box(box_id, &name)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_box(db: State<'_, Database>, box_id: i64) -> Result<(), String> {
+    db.delete_box(box_id).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn reorder_boxes(
+    db: State<'_, Database>,
+    trainer_id: i64,
+    box_ids: Vec<i64>,
+) -> Result<(), String> {
+    db.reorder_boxes(trainer_id, &box_ids)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn move_pokemon(
+    db: State<'_, Database>,
+    user_pokemon_id: i64,
+    location: StorageLocation,
+) -> Result<(), String> {
+    db.move_pokemon(user_pokemon_id, location)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn swap_pokemon(
+    db: State<'_, Database>,
+    first_id: i64,
+    second_id: i64,
+) -> Result<(), String> {
+    db.swap_pokemon(first_id, second_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn deposit_pokemon(
+    db: State<'_, Database>,
+    user_pokemon_id: i64,
+    box_id: i64,
+    position: Option<u32>,
+) -> Result<(), String> {
+    db.deposit_pokemon(user_pokemon_id, box_id, position)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn withdraw_pokemon(db: State<'_, Database>, user_pokemon_id: i64) -> Result<(), String> {
+    db.withdraw_pokemon(user_pokemon_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 // File operations
 #[tauri::command]
 pub async fn export_stat_block(stat_block: DnDStatBlock, format: String) -> Result<String, String> {
@@ -186,51 +690,109 @@ fn format_stat_block_as_text(stat_block: &DnDStatBlock) -> String {
         r#"
 === D&D 5e Stat Block ===
 
+Size: {}
+
 Ability Scores:
 STR: {} ({:+})  DEX: {} ({:+})  CON: {} ({:+})
 INT: {} ({:+})  WIS: {} ({:+})  CHA: {} ({:+})
 
 Armor Class: {}
-Hit Points: {}
+Hit Points: {} ({})
 Speed: {} ft.
+Carrying Capacity: {} lb.
+Saving Throws: {}
 Challenge Rating: {}
+Proficiency Bonus: {:+}
+Ability: {}
+Held Item: {}
 
 Damage Resistances: {}
 Damage Vulnerabilities: {}
+Damage Immunities: {}
 
-Actions: {} actions available
+Actions:
+{}
 "#,
+        stat_block.size,
         stat_block.ability_scores.strength,
-        (stat_block.ability_scores.strength as i8 - 10) / 2,
+        ability_modifier(stat_block.ability_scores.strength),
         stat_block.ability_scores.dexterity,
-        (stat_block.ability_scores.dexterity as i8 - 10) / 2,
+        ability_modifier(stat_block.ability_scores.dexterity),
         stat_block.ability_scores.constitution,
-        (stat_block.ability_scores.constitution as i8 - 10) / 2,
+        ability_modifier(stat_block.ability_scores.constitution),
         stat_block.ability_scores.intelligence,
-        (stat_block.ability_scores.intelligence as i8 - 10) / 2,
+        ability_modifier(stat_block.ability_scores.intelligence),
         stat_block.ability_scores.wisdom,
-        (stat_block.ability_scores.wisdom as i8 - 10) / 2,
+        ability_modifier(stat_block.ability_scores.wisdom),
         stat_block.ability_scores.charisma,
-        (stat_block.ability_scores.charisma as i8 - 10) / 2,
+        ability_modifier(stat_block.ability_scores.charisma),
         stat_block.armor_class,
+        stat_block.hit_dice,
         stat_block.hit_points,
         stat_block.speed,
+        stat_block.carrying_capacity,
+        format_saving_throws(stat_block),
         stat_block.challenge_rating,
-        if stat_block.resistances.is_empty() {
+        stat_block.proficiency_bonus,
+        stat_block.ability.as_deref().unwrap_or("None"),
+        stat_block.held_item.as_deref().unwrap_or("None"),
+        format_damage_types(&stat_block.resistances),
+        format_damage_types(&stat_block.vulnerabilities),
+        format_damage_types(&stat_block.immunities),
+        if stat_block.actions.is_empty() {
             "None".to_string()
         } else {
-            format!("{} types", stat_block.resistances.len())
-        },
-        if stat_block.vulnerabilities.is_empty() {
-            "None".to_string()
-        } else {
-            format!("{} types", stat_block.vulnerabilities.len())
-        },
-        stat_block.actions.len()
+            stat_block
+                .actions
+                .iter()
+                .map(|a| match &a.recharge {
+                    Some(recharge) => {
+                        format!("{} (Recharge {}). {}", a.name, recharge, a.description)
+                    }
+                    None => format!("{}. {}", a.name, a.description),
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
     )
 }
 
+fn format_saving_throws(stat_block: &DnDStatBlock) -> String {
+    let proficient: Vec<String> = stat_block
+        .saving_throws
+        .iter()
+        .filter(|s| s.proficient)
+        .map(|s| format!("{} {:+}", &s.ability[..3].to_uppercase(), s.modifier))
+        .collect();
+
+    if proficient.is_empty() {
+        "None".to_string()
+    } else {
+        proficient.join(", ")
+    }
+}
+
+fn format_damage_types(damage_types: &[DamageType]) -> String {
+    if damage_types.is_empty() {
+        "None".to_string()
+    } else {
+        damage_types
+            .iter()
+            .map(|t| t.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
 // Cache management commands
+// Whether data is being saved; false when the database file could not be opened
+#[tauri::command]
+pub async fn get_database_status(
+    status: State<'_, DatabaseStatus>,
+) -> Result<DatabaseStatus, String> {
+    Ok(status.inner().clone())
+}
+
 #[tauri::command]
 pub async fn clear_pokemon_cache(db: State<'_, Database>) -> Result<(), String> {
     db.clear_pokemon_cache()
@@ -294,6 +856,7 @@ pub async fn get_pokemon_batch(
 #[tauri::command]
 pub async fn get_pokemon_list(
     db: State<'_, Database>,
+    source: State<'_, SharedSource>,
     offset: u32,
     limit: u32,
     type_filter: Option<String>,
@@ -318,148 +881,365 @@ pub async fn get_pokemon_list(
         });
     }
 
-    // If no cached results, fetch from API and cache
-    let api_url = if let Some(ref type_name) = type_filter {
-        format!("{}/type/{}", POKEAPI_BASE_URL, type_name)
-    } else {
-        format!(
-            "{}/pokemon?limit={}&offset={}",
-            POKEAPI_BASE_URL, limit, offset
-        )
-    };
+    // If no cached results, fetch from the source and cache
+    if let Some(ref type_name) = type_filter {
+        // Handle type-specific response
+        let type_response = source
+            .get::<PokeApiTypeResponse>(&format!("type/{}", type_name))
+            .await
+            .map_err(|e| format!("Failed to fetch Pokemon list: {}", e))?;
 
-    match reqwest::get(&api_url).await {
-        Ok(response) => {
-            if response.status().is_success() {
-                if type_filter.is_some() {
-                    // Handle type-specific response
-                    match response.json::<PokeApiTypeResponse>().await {
-                        Ok(type_response) => {
-                            let mut results = Vec::new();
-                            let pokemon_refs = type_response
-                                .pokemon
-                                .into_iter()
-                                .skip(offset as usize)
-                                .take(limit as usize);
-
-                            for pokemon_ref in pokemon_refs {
-                                if let Some(id_str) = pokemon_ref.pokemon.url.split('/').nth_back(1)
-                                {
-                                    if let Ok(id) = id_str.parse::<u32>() {
-                                        if let Ok(pokemon) = fetch_pokemon(db.clone(), id).await {
-                                            results.push(pokemon);
-                                        }
-                                    }
-                                }
-                            }
-
-                            Ok(PokemonListResponse {
-                                pokemon: results.clone(),
-                                total_count: results.len() as u32,
-                                has_more: false,
-                            })
-                        }
-                        Err(e) => Err(format!("Failed to parse type response: {}", e)),
-                    }
-                } else {
-                    // Handle general Pokemon list response
-                    match response.json::<PokeApiResponse<PokeApiResource>>().await {
-                        Ok(list_response) => {
-                            let mut results = Vec::new();
-
-                            for resource in list_response.results {
-                                if let Some(id_str) = resource.url.split('/').nth_back(1) {
-                                    if let Ok(id) = id_str.parse::<u32>() {
-                                        if let Ok(pokemon) = fetch_pokemon(db.clone(), id).await {
-                                            // Apply search filter if specified
-                                            if let Some(ref query) = search_query {
-                                                if !pokemon
-                                                    .name
-                                                    .to_lowercase()
-                                                    .contains(&query.to_lowercase())
-                                                {
-                                                    continue;
-                                                }
-                                            }
-                                            results.push(pokemon);
-                                        }
-                                    }
-                                }
-                            }
-                            Ok(PokemonListResponse {
-                                pokemon: results,
-                                total_count: list_response.count,
-                                has_more: (offset + limit) < list_response.count,
-                            })
-                        }
-                        Err(e) => Err(format!("Failed to parse Pokemon list response: {}", e)),
+        let mut results = Vec::new();
+        let pokemon_refs = type_response
+            .pokemon
+            .into_iter()
+            .skip(offset as usize)
+            .take(limit as usize);
+
+        for pokemon_ref in pokemon_refs {
+            if let Some(id) = pokemon_ref.pokemon.id() {
+                if let Ok(pokemon) = load_pokemon(&db, source.as_ref(), id).await {
+                    results.push(pokemon);
+                }
+            }
+        }
+
+        return Ok(PokemonListResponse {
+            pokemon: results.clone(),
+            total_count: results.len() as u32,
+            has_more: false,
+        });
+    }
+
+    // Handle general Pokemon list response
+    let list_response = source
+        .get::<PokeApiResponse<PokeApiResource>>(&format!(
+            "pokemon?limit={}&offset={}",
+            limit, offset
+        ))
+        .await
+        .map_err(|e| format!("Failed to fetch Pokemon list: {}", e))?;
+
+    let mut results = Vec::new();
+    for resource in list_response.results {
+        if let Some(id) = resource.id() {
+            if let Ok(pokemon) = load_pokemon(&db, source.as_ref(), id).await {
+                // Apply search filter if specified
+                if let Some(ref query) = search_query {
+                    if !pokemon.name.to_lowercase().contains(&query.to_lowercase()) {
+                        continue;
                     }
                 }
-            } else {
-                Err(format!("API returned status: {}", response.status()))
+                results.push(pokemon);
             }
         }
-        Err(e) => Err(format!("Failed to fetch Pokemon list: {}", e)),
     }
+    Ok(PokemonListResponse {
+        pokemon: results,
+        total_count: list_response.count,
+        has_more: (offset + limit) < list_response.count,
+    })
 }
 
+// Structured Pokedex query over the cached Pokemon, with every filter bound as a parameter
+#[tauri::command]
+pub async fn query_pokemon(
+    db: State<'_, Database>,
+    query: PokemonQuery,
+    offset: u32,
+    limit: u32,
+) -> Result<PokemonListResponse, String> {
+    let pokemon = db
+        .query_pokemon(&query, Some(offset), Some(limit))
+        .await
+        .map_err(|e| format!("Failed to query Pokemon: {}", e))?;
+    let total_count = db
+        .count_pokemon(&query)
+        .await
+        .map_err(|e| format!("Failed to count Pokemon: {}", e))? as u32;
+
+    Ok(PokemonListResponse {
+        pokemon,
+        total_count,
+        has_more: (offset + limit) < total_count,
+    })
+}
+
+// Load one generation's species (Gen 1 if none is given), or every species in a
+// Pokedex such as "kanto" or "paldea" when one is named
 #[tauri::command]
 pub async fn initialize_pokemon_data(
+    app: AppHandle,
     db: State<'_, Database>,
+    source: State<'_, SharedSource>,
+    cancellation: State<'_, LoadCancellation>,
     generation: Option<u32>,
+    pokedex: Option<String>,
 ) -> Result<String, String> {
-    let gen_limit = match generation {
-        Some(1) => 151, // Gen 1: 1-151
-        Some(2) => 251, // Gen 1-2: 1-251
-        Some(3) => 386, // Gen 1-3: 1-386
-        Some(4) => 493, // Gen 1-4: 1-493
-        Some(5) => 649, // Gen 1-5: 1-649
-        _ => 151,       // Default to Gen 1
-    };
+    // Cancellable from the start, while the species list is still being prepared
+    let token = cancellation.start();
+    let ids = selected_species_ids(&db, source.as_ref(), generation, pokedex).await?;
+    initialize_species(&app, &db, &source, &token, ids).await
+}
 
-    let mut loaded_count = 0;
-    let mut error_count = 0;
+// Load every species of every known generation. This is the full download of about a
+// thousand Pokemon, so it only runs when asked for.
+#[tauri::command]
+pub async fn initialize_all_pokemon_data(
+    app: AppHandle,
+    db: State<'_, Database>,
+    source: State<'_, SharedSource>,
+    cancellation: State<'_, LoadCancellation>,
+) -> Result<String, String> {
+    let token = cancellation.start();
+    ensure_generation_data(&db, source.as_ref()).await?;
+    let mut ids: Vec<u32> = db
+        .get_generations()
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .flat_map(|generation| generation.species_ids)
+        .collect();
+    ids.sort_unstable();
 
-    println!(
-        "Starting bulk Pokemon initialization for {} Pokemon...",
-        gen_limit
-    );
+    initialize_species(&app, &db, &source, &token, ids).await
+}
 
-    for id in 1..=gen_limit {
-        // Check if Pokemon is already cached
-        if let Ok(Some(_)) = db.get_cached_pokemon(id).await {
-            continue; // Skip if already cached
-        }
+// Species in the named Pokedex, or else in the given generation (Gen 1 by default)
+async fn selected_species_ids(
+    db: &Database,
+    source: &dyn PokemonSource,
+    generation: Option<u32>,
+    pokedex: Option<String>,
+) -> Result<Vec<u32>, String> {
+    ensure_generation_data(db, source).await?;
 
-        // Fetch from API
-        match fetch_pokemon(db.clone(), id).await {
-            Ok(_) => {
-                loaded_count += 1;
-                if loaded_count % 10 == 0 {
-                    println!("Loaded {} Pokemon...", loaded_count);
-                }
+    match pokedex {
+        Some(pokedex) => {
+            let ids = db
+                .get_pokedex_species_ids(&pokedex.to_lowercase())
+                .await
+                .map_err(|e| e.to_string())?;
+            if ids.is_empty() {
+                return Err(format!("Unknown Pokedex: {}", pokedex));
             }
-            Err(e) => {
-                error_count += 1;
-                eprintln!("Failed to load Pokemon {}: {}", id, e);
+            Ok(ids)
+        }
+        None => {
+            let generation = generation.unwrap_or(1);
+            let ids = db
+                .get_generation_species_ids(generation)
+                .await
+                .map_err(|e| e.to_string())?;
+            if ids.is_empty() {
+                return Err(format!("Unknown generation: {}", generation));
             }
+            Ok(ids)
         }
-
-        // Small delay to avoid overwhelming PokeAPI
-        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
     }
+}
+
+async fn initialize_species(
+    app: &AppHandle,
+    db: &Database,
+    source: &SharedSource,
+    token: &LoadToken,
+    ids: Vec<u32>,
+) -> Result<String, String> {
+    println!(
+        "Starting bulk Pokemon initialization for {} Pokemon...",
+        ids.len()
+    );
 
+    let report = bulk_load(app, db, source, token, ids).await?;
     let message = format!(
-        "Initialization complete: {} Pokemon loaded, {} errors",
-        loaded_count, error_count
+        "Initialization {}: {} Pokemon loaded, {} errors",
+        if report.cancelled {
+            "cancelled"
+        } else {
+            "complete"
+        },
+        report.loaded.len(),
+        report.failed.len()
     );
     println!("{}", message);
     Ok(message)
 }
 
+// Store every generation and Pokedex the source knows, replacing what was there.
+// Returns how many of each were saved.
+async fn sync_generation_data(
+    db: &Database,
+    source: &dyn PokemonSource,
+) -> Result<(usize, usize), String> {
+    let generations = source
+        .get::<PokeApiResponse<PokeApiResource>>(&format!(
+            "generation?limit={}",
+            METADATA_LIST_LIMIT
+        ))
+        .await
+        .map_err(|e| format!("Failed to fetch generation list: {}", e))?;
+    for resource in &generations.results {
+        let response = source
+            .get::<PokeApiGenerationResponse>(&format!("generation/{}", resource.name))
+            .await
+            .map_err(|e| format!("Failed to fetch generation {}: {}", resource.name, e))?;
+        db.save_generation(&Generation::from_pokeapi_response(&response))
+            .await
+            .map_err(|e| format!("Failed to save generation {}: {}", resource.name, e))?;
+    }
+
+    let pokedexes = source
+        .get::<PokeApiResponse<PokeApiResource>>(&format!("pokedex?limit={}", METADATA_LIST_LIMIT))
+        .await
+        .map_err(|e| format!("Failed to fetch Pokedex list: {}", e))?;
+    for resource in &pokedexes.results {
+        let response = source
+            .get::<PokeApiPokedexResponse>(&format!("pokedex/{}", resource.name))
+            .await
+            .map_err(|e| format!("Failed to fetch Pokedex {}: {}", resource.name, e))?;
+        let (pokedex, entries) = Pokedex::from_pokeapi_response(&response);
+        db.save_pokedex(&pokedex, &entries)
+            .await
+            .map_err(|e| format!("Failed to save Pokedex {}: {}", resource.name, e))?;
+    }
+
+    Ok((generations.results.len(), pokedexes.results.len()))
+}
+
+// Generation data is fetched the first time something needs it
+async fn ensure_generation_data(db: &Database, source: &dyn PokemonSource) -> Result<(), String> {
+    let generations = db.get_generations().await.map_err(|e| e.to_string())?;
+    if generations.is_empty() {
+        sync_generation_data(db, source).await?;
+    }
+    Ok(())
+}
+
+// Re-fetch generations and Pokedexes, e.g. after a new game adds some
+#[tauri::command]
+pub async fn refresh_generation_data(
+    db: State<'_, Database>,
+    source: State<'_, SharedSource>,
+) -> Result<String, String> {
+    let (generation_count, pokedex_count) = sync_generation_data(&db, source.as_ref()).await?;
+    Ok(format!(
+        "Loaded {} generations and {} Pokedexes",
+        generation_count, pokedex_count
+    ))
+}
+
+#[tauri::command]
+pub async fn get_generations(
+    db: State<'_, Database>,
+    source: State<'_, SharedSource>,
+) -> Result<Vec<Generation>, String> {
+    ensure_generation_data(&db, source.as_ref()).await?;
+    db.get_generations().await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_pokedexes(
+    db: State<'_, Database>,
+    source: State<'_, SharedSource>,
+) -> Result<Vec<Pokedex>, String> {
+    ensure_generation_data(&db, source.as_ref()).await?;
+    db.get_pokedexes().await.map_err(|e| e.to_string())
+}
+
+// A species' National and regional dex numbers
+#[tauri::command]
+pub async fn get_dex_entries(
+    db: State<'_, Database>,
+    source: State<'_, SharedSource>,
+    species_id: u32,
+) -> Result<Vec<DexEntry>, String> {
+    ensure_generation_data(&db, source.as_ref()).await?;
+    db.get_dex_entries(species_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// Load the given Pokemon that are not cached yet, several at a time, retrying transient
+// failures and reporting progress as events. Whatever still fails is recorded for
+// retry_failed_pokemon_loads.
+async fn bulk_load(
+    app: &AppHandle,
+    db: &Database,
+    source: &SharedSource,
+    token: &LoadToken,
+    ids: Vec<u32>,
+) -> Result<LoadReport, String> {
+    let cached: HashSet<u32> = db
+        .get_cached_pokemon_ids()
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .collect();
+    let ids = ids.into_iter().filter(|id| !cached.contains(id)).collect();
+
+    let progress_app = app.clone();
+    let job_db = db.clone();
+    let job_source = source.clone();
+    let report = loader::load_all(
+        ids,
+        &LoaderOptions::default(),
+        token.subscribe(),
+        move |progress| {
+            // Nobody listening is fine
+            let _ = progress_app.emit_all(LOAD_PROGRESS_EVENT, progress);
+        },
+        move |id| {
+            let db = job_db.clone();
+            let source = job_source.clone();
+            async move { download_pokemon(&db, source.as_ref(), id).await.map(|_| ()) }
+        },
+    )
+    .await;
+
+    db.record_load_report(&report)
+        .await
+        .map_err(|e| format!("Failed to record bulk load results: {}", e))?;
+    Ok(report)
+}
+
+// Pokemon that failed to load in earlier bulk loads
+#[tauri::command]
+pub async fn get_failed_pokemon_loads(db: State<'_, Database>) -> Result<Vec<FailedLoad>, String> {
+    db.get_failed_loads().await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn retry_failed_pokemon_loads(
+    app: AppHandle,
+    db: State<'_, Database>,
+    source: State<'_, SharedSource>,
+    cancellation: State<'_, LoadCancellation>,
+) -> Result<LoadReport, String> {
+    let token = cancellation.start();
+    let ids = db
+        .get_failed_loads()
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|failure| failure.pokemon_id)
+        .collect();
+
+    bulk_load(&app, &db, &source, &token, ids).await
+}
+
+// Stop the bulk loads running now. Pokemon loaded so far stay cached, and the next
+// load picks up the rest.
+#[tauri::command]
+pub async fn cancel_pokemon_load(cancellation: State<'_, LoadCancellation>) -> Result<(), String> {
+    cancellation.cancel();
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn get_pokemon_list_improved(
     db: State<'_, Database>,
+    source: State<'_, SharedSource>,
     offset: u32,
     limit: u32,
     type_filter: Option<String>,
@@ -483,46 +1263,31 @@ pub async fn get_pokemon_list_improved(
             total_count: total_count as u32,
             has_more: (offset + limit) < total_count as u32,
         });
-    } // If no cached data and no filters, try to fetch a batch from PokeAPI
+    } // If no cached data and no filters, try to fetch a batch from the source
     if type_filter.is_none() && search_query.is_none() {
-        let url = format!(
-            "{}/pokemon?limit={}&offset={}",
-            POKEAPI_BASE_URL, limit, offset
-        );
+        let path = format!("pokemon?limit={}&offset={}", limit, offset);
 
-        match reqwest::get(&url).await {
-            Ok(response) if response.status().is_success() => {
-                match response.json::<PokeApiResponse<PokeApiResource>>().await {
-                    Ok(list_response) => {
-                        let mut results = Vec::new();
-
-                        // Fetch each Pokemon and cache it
-                        for resource in list_response.results {
-                            if let Some(id_str) = resource.url.split('/').nth_back(1) {
-                                if let Ok(id) = id_str.parse::<u32>() {
-                                    if let Ok(pokemon) = fetch_pokemon(db.clone(), id).await {
-                                        results.push(pokemon);
-                                    }
-                                }
-                            }
-                        }
+        match source.get::<PokeApiResponse<PokeApiResource>>(&path).await {
+            Ok(list_response) => {
+                let mut results = Vec::new();
 
-                        return Ok(PokemonListResponse {
-                            pokemon: results,
-                            total_count: list_response.count,
-                            has_more: (offset + limit) < list_response.count,
-                        });
-                    }
-                    Err(e) => {
-                        eprintln!("Failed to parse PokeAPI response: {}", e);
+                // Fetch each Pokemon and cache it
+                for resource in list_response.results {
+                    if let Some(id) = resource.id() {
+                        if let Ok(pokemon) = load_pokemon(&db, source.as_ref(), id).await {
+                            results.push(pokemon);
+                        }
                     }
                 }
-            }
-            Ok(response) => {
-                eprintln!("PokeAPI returned error status: {}", response.status());
+
+                return Ok(PokemonListResponse {
+                    pokemon: results,
+                    total_count: list_response.count,
+                    has_more: (offset + limit) < list_response.count,
+                });
             }
             Err(e) => {
-                eprintln!("Failed to fetch from PokeAPI: {}", e);
+                eprintln!("Failed to fetch from {}: {}", source.describe(), e);
             }
         }
     }
@@ -535,60 +1300,121 @@ pub async fn get_pokemon_list_improved(
     })
 }
 
-// Helper commands
+// Fill the Pokemon, move, type, species, generation and Pokedex tables from a local copy
+// of PokeAPI's api-data repository, so initialize_pokemon_data finds everything without a
+// network. Pokemon already in the cache are refreshed from the dump. Entries that fail
+// are reported and recorded like failed bulk loads, for get_failed_pokemon_loads.
 #[tauri::command]
-pub async fn ensure_pokemon_database_initialized(
-    db: State<'_, Database>,
-    max_id: Option<u32>,
-) -> Result<String, String> {
-    let expected_count = max_id.unwrap_or(1010); // Default to Gen 1-8 National Dex
-    if db
-        .is_pokemon_database_complete(expected_count)
+pub async fn import_api_data(db: State<'_, Database>, path: String) -> Result<LoadReport, String> {
+    let data = tokio::task::spawn_blocking(move || ApiDataSource::open(Path::new(&path)))
         .await
-        .map_err(|e| e.to_string())?
-    {
-        return Ok("Database already complete".to_string());
-    }
+        .map_err(|e| e.to_string())??;
+    let source: &dyn PokemonSource = &data;
+    println!("Importing Pokemon from {}", source.describe());
 
-    // Bulk fetch all Pokemon resources from the API
-    let url = format!("https://pokeapi.co/api/v2/pokemon?limit={}", expected_count);
-    let response = reqwest::get(&url)
-        .await
-        .map_err(|e| format!("Failed to fetch Pokemon list: {}", e))?;
-    if !response.status().is_success() {
-        return Err(format!(
-            "PokeAPI returned error status: {}",
-            response.status()
-        ));
-    }
-    let list_response: crate::pokemon::PokeApiResponse<crate::pokemon::PokeApiResource> = response
-        .json()
+    // Ask for the whole list in one page
+    let list_response = source
+        .get::<PokeApiResponse<PokeApiResource>>(&format!("pokemon?limit={}", u32::MAX))
         .await
-        .map_err(|e| format!("Failed to parse Pokemon list: {}", e))?;
-    let mut loaded_count = 0;
-    let mut error_count = 0;
+        .map_err(|e| e.to_string())?;
+    let mut report = LoadReport::default();
     for resource in list_response.results {
-        if let Some(id_str) = resource.url.split('/').nth_back(1) {
-            if let Ok(id) = id_str.parse::<u32>() {
-                // Only fetch if not already cached
-                if let Ok(Some(_)) = db.get_cached_pokemon(id).await {
-                    continue;
-                }
-                match fetch_pokemon(db.clone(), id).await {
-                    Ok(_) => loaded_count += 1,
-                    Err(e) => {
-                        error_count += 1;
-                        eprintln!("Failed to load Pokemon {}: {}", id, e);
-                    }
+        let Some(id) = resource.id() else {
+            continue;
+        };
+        match download_pokemon(&db, source, id).await {
+            Ok(_) => {
+                let _ = db.mark_pokemon_imported(id).await;
+                report.loaded.push(id);
+                if report.loaded.len() % 100 == 0 {
+                    println!("Imported {} Pokemon...", report.loaded.len());
                 }
             }
+            Err(e) => {
+                eprintln!("Failed to import Pokemon {}: {}", id, e);
+                report.failed.push(FailedLoad {
+                    pokemon_id: id,
+                    error: e.to_string(),
+                    attempts: 1,
+                });
+            }
         }
     }
-    let message = format!(
-        "Initialization complete: {} loaded, {} errors",
-        loaded_count, error_count
+
+    db.record_load_report(&report)
+        .await
+        .map_err(|e| format!("Failed to record import results: {}", e))?;
+
+    // Generations, Pokedexes and the type chart come from the dump too. The Pokemon are
+    // already imported, so a failure here is logged rather than losing the report.
+    if let Err(e) = sync_generation_data(&db, source).await {
+        eprintln!("Failed to import generations and Pokedexes: {}", e);
+    }
+    match sync_type_chart(&db, source).await {
+        Ok(count) => println!("Imported the type chart for {} types", count),
+        Err(e) => eprintln!("Failed to import the type chart: {}", e),
+    }
+
+    println!(
+        "Import complete: {} Pokemon imported, {} errors",
+        report.loaded.len(),
+        report.failed.len()
     );
-    Ok(message)
+    Ok(report)
+}
+
+// Replace the stored type chart with the source's damage relations
+async fn sync_type_chart(db: &Database, source: &dyn PokemonSource) -> Result<usize, String> {
+    let types = source
+        .get::<PokeApiResponse<PokeApiResource>>(&format!("type?limit={}", METADATA_LIST_LIMIT))
+        .await
+        .map_err(|e| format!("Failed to fetch type list: {}", e))?;
+
+    let mut responses = Vec::new();
+    for resource in &types.results {
+        let response = source
+            .get::<PokeApiTypeResponse>(&format!("type/{}", resource.name))
+            .await
+            .map_err(|e| format!("Failed to fetch type {}: {}", resource.name, e))?;
+        responses.push(response);
+    }
+    if responses.is_empty() {
+        return Err("The source has no types".to_string());
+    }
+
+    db.save_type_chart(&TypeChart::from_pokeapi_responses(&responses))
+        .await
+        .map_err(|e| format!("Failed to save the type chart: {}", e))?;
+    Ok(responses.len())
+}
+
+// Helper commands
+
+// Make sure the selected generation (Gen 1 by default) or Pokedex is cached, as the
+// Pokedex page does on launch. Loading everything is left to initialize_all_pokemon_data.
+#[tauri::command]
+pub async fn ensure_pokemon_database_initialized(
+    app: AppHandle,
+    db: State<'_, Database>,
+    source: State<'_, SharedSource>,
+    cancellation: State<'_, LoadCancellation>,
+    generation: Option<u32>,
+    pokedex: Option<String>,
+) -> Result<String, String> {
+    let token = cancellation.start();
+    let ids = selected_species_ids(&db, source.as_ref(), generation, pokedex).await?;
+
+    let cached: HashSet<u32> = db
+        .get_cached_pokemon_ids()
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .collect();
+    if ids.iter().all(|id| cached.contains(id)) {
+        return Ok("Database already complete".to_string());
+    }
+
+    initialize_species(&app, &db, &source, &token, ids).await
 }
 
 #[derive(serde::Serialize)]
@@ -598,15 +1424,14 @@ pub struct PokemonListResponse {
     pub has_more: bool,
 }
 
-#[derive(serde::Deserialize)]
-pub struct PokeApiTypeResponse {
-    pub pokemon: Vec<PokeApiTypePokemon>,
-}
-
-#[derive(serde::Deserialize)]
-pub struct PokeApiTypePokemon {
-    pub pokemon: PokeApiResource,
-    pub slot: u8,
+// Outcome of award_experience, for the level-up screen
+#[derive(serde::Serialize)]
+pub struct LevelUpReport {
+    pub pokemon: UserPokemon,
+    pub previous_level: u8,
+    pub experience_to_next_level: u32,
+    pub new_moves: Vec<Move>,
+    pub available_evolutions: Vec<EvolutionNode>,
 }
 
 #[derive(serde::Serialize)]
@@ -614,3 +1439,215 @@ pub struct CacheStats {
     pub cached_pokemon_count: i64,
     pub last_updated: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::FixtureSource;
+    use crate::test_support::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn loading_a_pokemon_caches_it_with_its_moves_and_species() {
+        let db = Database::in_memory().await.unwrap();
+
+        let pokemon = load_pokemon(&db, &pikachu_source(), 25).await.unwrap();
+        assert_eq!(pokemon.name, "pikachu");
+        assert_eq!(pokemon.base_stats.speed, 90);
+        assert_eq!(pokemon.moves.len(), 1);
+        assert_eq!(pokemon.moves[0].power, Some(40));
+
+        assert!(db.get_cached_move("thunder-shock").await.unwrap().is_some());
+        let species = db.get_cached_species(25).await.unwrap().unwrap();
+        assert_eq!(species.genus.as_deref(), Some("Mouse Pokémon"));
+        assert_eq!(species.evolution_chain_id, Some(10));
+
+        // A fresh cached copy needs no source at all
+        let cached = load_pokemon(&db, &FixtureSource::new(), 25).await.unwrap();
+        assert_eq!(cached.moves.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn missing_pokemon_are_reported() {
+        let db = Database::in_memory().await.unwrap();
+
+        let result = load_pokemon(&db, &pikachu_source(), 26).await;
+        assert!(matches!(result, Err(SourceError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn pokemon_are_not_cached_without_their_moves_or_species() {
+        let db = Database::in_memory().await.unwrap();
+
+        let without_move = fixture_source(vec![
+            ("pokemon", pikachu()),
+            ("pokemon-species", pikachu_species()),
+        ]);
+        assert!(download_pokemon(&db, &without_move, 25).await.is_err());
+        assert!(db.get_cached_pokemon(25).await.unwrap().is_none());
+
+        let without_species =
+            fixture_source(vec![("pokemon", pikachu()), ("move", thunder_shock())]);
+        assert!(download_pokemon(&db, &without_species, 25).await.is_err());
+        assert!(db.get_cached_pokemon(25).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn stale_pokemon_are_served_when_the_source_fails() {
+        let db = TempDatabase::new().await;
+        load_pokemon(&db, &pikachu_source(), 25).await.unwrap();
+
+        let pool = sqlx::SqlitePool::connect(&format!("sqlite://{}", db.path().display()))
+            .await
+            .unwrap();
+        sqlx::query("UPDATE pokemon_cache SET last_updated = '2000-01-01T00:00:00+00:00'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        pool.close().await;
+        assert!(!db.is_pokemon_cache_valid(25, 24).await.unwrap());
+
+        let stale = load_pokemon(&db, &FixtureSource::new(), 25).await.unwrap();
+        assert_eq!(stale.name, "pikachu");
+
+        // Imported copies never go stale
+        db.mark_pokemon_imported(25).await.unwrap();
+        assert!(db.is_pokemon_cache_valid(25, 24).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn species_and_evolutions_come_from_the_source_once() {
+        let db = Database::in_memory().await.unwrap();
+        let source = pikachu_source();
+
+        let species = fetch_species(&db, &source, 25).await.unwrap();
+        assert_eq!(species.evolves_from.as_deref(), Some("pichu"));
+        let evolutions = fetch_evolutions(&db, &source, &species).await.unwrap();
+        assert_eq!(evolutions.len(), 1);
+        assert_eq!(evolutions[0].species_name, "raichu");
+        assert_eq!(evolutions[0].species_id, 26);
+        assert_eq!(
+            evolutions[0].conditions[0].item.as_deref(),
+            Some("thunder-stone")
+        );
+
+        // Both are cached now
+        let offline = FixtureSource::new();
+        let species = fetch_species(&db, &offline, 25).await.unwrap();
+        assert_eq!(
+            fetch_evolutions(&db, &offline, &species)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(fetch_species(&db, &offline, 26).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn generations_and_pokedexes_select_species() {
+        let db = Database::in_memory().await.unwrap();
+        let source = fixture_source(kanto_generation_and_pokedex());
+
+        assert_eq!(sync_generation_data(&db, &source).await, Ok((1, 1)));
+
+        let generation = selected_species_ids(&db, &source, None, None).await;
+        assert_eq!(generation, Ok(vec![1, 25]));
+        let pokedex = selected_species_ids(&db, &source, None, Some("Kanto".to_string())).await;
+        assert_eq!(pokedex, Ok(vec![1, 25]));
+
+        assert!(selected_species_ids(&db, &source, Some(2), None)
+            .await
+            .is_err());
+        assert!(
+            selected_species_ids(&db, &source, None, Some("johto".to_string()))
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn type_charts_are_imported_from_the_source() {
+        let db = Database::in_memory().await.unwrap();
+        assert_eq!(db.get_type_chart().await.unwrap(), TypeChart::default());
+
+        let electric = json!({
+            "id": 13,
+            "name": "electric",
+            "damage_relations": {
+                "double_damage_to": [resource("type", "water", 11)],
+                "half_damage_to": [resource("type", "grass", 12)],
+                "no_damage_to": [resource("type", "ground", 5)],
+            },
+        });
+        let source = fixture_source(vec![("type", electric)]);
+        assert_eq!(sync_type_chart(&db, &source).await, Ok(1));
+
+        let chart = db.get_type_chart().await.unwrap();
+        assert_eq!(chart.effectiveness("electric", "ground"), 0.0);
+        assert_eq!(chart.effectiveness("electric", "water"), 2.0);
+        assert_eq!(chart.effectiveness("fire", "grass"), 1.0);
+        assert!(sync_type_chart(&db, &FixtureSource::new()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn generation_data_is_fetched_when_first_needed() {
+        let db = Database::in_memory().await.unwrap();
+
+        assert!(selected_species_ids(&db, &FixtureSource::new(), None, None)
+            .await
+            .is_err());
+        let source = fixture_source(kanto_generation_and_pokedex());
+        assert_eq!(
+            selected_species_ids(&db, &source, Some(1), None).await,
+            Ok(vec![1, 25])
+        );
+        assert_eq!(db.get_generations().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn profiles_are_resolved_by_name_and_validated() {
+        let db = Database::in_memory().await.unwrap();
+
+        let heroic = resolve_profile(&db, Some("heroic".to_string()), Some(HitPointMethod::Roll))
+            .await
+            .unwrap();
+        assert_eq!(heroic.stat_scaling.max_ability_score, 24);
+        assert_eq!(heroic.hit_point_method, HitPointMethod::Roll);
+
+        assert!(resolve_profile(&db, Some("epic".to_string()), None)
+            .await
+            .is_err());
+
+        let mut broken = ConversionProfile {
+            name: "broken".to_string(),
+            ..Default::default()
+        };
+        broken.stat_scaling.min_ability_score = 25;
+        db.save_conversion_profile(&broken).await.unwrap();
+        assert!(resolve_profile(&db, Some("broken".to_string()), None)
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn text_export_lists_the_stat_block() {
+        let pokemon = Pokemon::from_pokeapi_response(
+            serde_json::from_value(pikachu()).unwrap(),
+            vec![Move::from_pokeapi_response(
+                serde_json::from_value(thunder_shock()).unwrap(),
+                1,
+            )],
+        );
+        let stat_block = DnDStatBlock::from_pokemon(
+            &pokemon,
+            20,
+            &ConversionProfile::default(),
+            &TypeChart::default(),
+        );
+
+        let text = format_stat_block_as_text(&stat_block);
+        assert!(text.contains(&format!("Armor Class: {}", stat_block.armor_class)));
+        assert!(text.contains("Thunder Shock"));
+    }
+}
@@ -1,17 +1,31 @@
-use crate::pokemon::{BaseStats, Pokemon};
+use crate::pokemon::{BaseStats, Move, Pokemon, PokemonCustomization};
+use crate::type_chart::{self, TypeChart};
+use rand::Rng;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
+// Field names follow the frontend's DnDStatBlock type
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct DnDStatBlock {
+    pub size: CreatureSize,
     pub ability_scores: AbilityScores,
     pub armor_class: u8,
     pub hit_points: u32,
+    pub hit_dice: HitDice,
     pub speed: u32,
+    /// In pounds
+    pub carrying_capacity: u32,
     pub skills: Vec<Skill>,
     pub resistances: Vec<DamageType>,
     pub vulnerabilities: Vec<DamageType>,
+    pub immunities: Vec<DamageType>,
     pub actions: Vec<Action>,
     pub challenge_rating: f32,
+    pub proficiency_bonus: i8,
+    pub saving_throws: Vec<SavingThrow>,
+    pub ability: Option<String>,
+    pub held_item: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,31 +43,204 @@ pub struct Skill {
     pub name: String,
     pub modifier: i8,
     pub proficient: bool,
+    pub expertise: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavingThrow {
+    pub ability: String,
+    pub modifier: i8,
+    pub proficient: bool,
+}
+
+/// Hit dice in 5e notation, e.g. 12d10 + 24
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HitDice {
+    pub count: u32,
+    pub die: u32,
+    pub modifier: i32,
+}
+
+impl HitDice {
+    /// Average hit points, rounded down as in the Monster Manual (at least 1)
+    pub fn average(&self) -> u32 {
+        let dice_average = (self.count * (self.die + 1) / 2) as i32;
+        (dice_average + self.modifier).max(1) as u32
+    }
+
+    /// Roll every die and add the modifier (at least 1)
+    pub fn roll(&self) -> u32 {
+        let mut rng = rand::thread_rng();
+        let rolled: u32 = (0..self.count).map(|_| rng.gen_range(1..=self.die)).sum();
+        (rolled as i32 + self.modifier).max(1) as u32
+    }
+}
+
+impl std::fmt::Display for HitDice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.modifier {
+            0 => write!(f, "{}d{}", self.count, self.die),
+            m if m > 0 => write!(f, "{}d{} + {}", self.count, self.die, m),
+            m => write!(f, "{}d{} - {}", self.count, self.die, -m),
+        }
+    }
+}
+
+/// Whether hit points take the hit dice average or a fresh roll
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HitPointMethod {
+    #[default]
+    Average,
+    Roll,
+}
+
+// 5e size categories, smallest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CreatureSize {
+    Tiny,
+    Small,
+    Medium,
+    Large,
+    Huge,
+    Gargantuan,
+}
+
+// Tallest Pokemon (in decimetres) and heaviest (in hectograms) that still fit each size
+// below Gargantuan: 2, 4, 8, 16 and 32 feet, and 10 kg to 10 tonnes
+const SIZE_LIMITS: [(CreatureSize, u32, u32); 5] = [
+    (CreatureSize::Tiny, 6, 100),
+    (CreatureSize::Small, 12, 600),
+    (CreatureSize::Medium, 24, 2500),
+    (CreatureSize::Large, 48, 20000),
+    (CreatureSize::Huge, 96, 100000),
+];
+
+impl CreatureSize {
+    /// Size from PokeAPI height and weight. Height picks the size, and a Pokemon too
+    /// heavy for that size (a Snorlax, say) moves up one. Unknown dimensions are Medium.
+    pub fn from_dimensions(height: u32, weight: u32) -> Self {
+        if height == 0 {
+            return CreatureSize::Medium;
+        }
+
+        let by_height = SIZE_LIMITS
+            .iter()
+            .position(|(_, max_height, _)| height < *max_height)
+            .unwrap_or(SIZE_LIMITS.len());
+        let too_heavy = SIZE_LIMITS
+            .get(by_height)
+            .is_some_and(|(_, _, max_weight)| weight > *max_weight);
+
+        match SIZE_LIMITS.get(by_height + too_heavy as usize) {
+            Some((size, _, _)) => *size,
+            None => CreatureSize::Gargantuan,
+        }
+    }
+
+    /// Hit die size for creatures of this size, as in the Monster Manual
+    pub fn hit_die(&self) -> u32 {
+        match self {
+            CreatureSize::Tiny => 4,
+            CreatureSize::Small => 6,
+            CreatureSize::Medium => 8,
+            CreatureSize::Large => 10,
+            CreatureSize::Huge => 12,
+            CreatureSize::Gargantuan => 20,
+        }
+    }
+
+    // Carrying capacity is halved for Tiny creatures and doubles per size above Medium
+    fn carrying_multiplier(&self) -> f32 {
+        match self {
+            CreatureSize::Tiny => 0.5,
+            CreatureSize::Small | CreatureSize::Medium => 1.0,
+            CreatureSize::Large => 2.0,
+            CreatureSize::Huge => 4.0,
+            CreatureSize::Gargantuan => 8.0,
+        }
+    }
+}
+
+impl std::fmt::Display for CreatureSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+// The 13 damage types of D&D 5e
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum DamageType {
+    Acid,
+    Bludgeoning,
+    Cold,
     Fire,
-    Water,
-    Grass,
-    Electric,
-    Psychic,
-    Ice,
-    Dragon,
-    Dark,
-    Fighting,
+    Force,
+    Lightning,
+    Necrotic,
+    Piercing,
     Poison,
-    Ground,
-    Flying,
-    Bug,
-    Rock,
-    Ghost,
-    Steel,
-    Fairy,
-    Normal,
+    Psychic,
+    Radiant,
+    Slashing,
+    Thunder,
+}
+
+impl std::fmt::Display for DamageType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = format!("{:?}", self).to_lowercase();
+        write!(f, "{}", name)
+    }
+}
+
+/// Which 5e damage type each Pokemon type deals and is judged against.
+/// Keys are PokeAPI type names; the default covers all 18 types and
+/// individual entries can be overridden per table.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DamageTypeMap {
+    pub mappings: HashMap<String, DamageType>,
+}
+
+impl DamageTypeMap {
+    pub fn damage_type_for(&self, pokemon_type: &str) -> Option<DamageType> {
+        self.mappings.get(pokemon_type).copied()
+    }
+}
+
+impl Default for DamageTypeMap {
+    fn default() -> Self {
+        let mappings = [
+            ("normal", DamageType::Bludgeoning),
+            ("fighting", DamageType::Bludgeoning),
+            ("flying", DamageType::Slashing),
+            ("poison", DamageType::Poison),
+            ("ground", DamageType::Bludgeoning),
+            ("rock", DamageType::Bludgeoning),
+            ("bug", DamageType::Piercing),
+            ("ghost", DamageType::Necrotic),
+            ("steel", DamageType::Slashing),
+            ("fire", DamageType::Fire),
+            ("water", DamageType::Bludgeoning),
+            ("grass", DamageType::Slashing),
+            ("electric", DamageType::Lightning),
+            ("psychic", DamageType::Psychic),
+            ("ice", DamageType::Cold),
+            ("dragon", DamageType::Force),
+            ("dark", DamageType::Necrotic),
+            ("fairy", DamageType::Radiant),
+        ]
+        .into_iter()
+        .map(|(pokemon_type, damage_type)| (pokemon_type.to_string(), damage_type))
+        .collect();
+
+        Self { mappings }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Action {
     pub name: String,
     pub description: String,
@@ -62,114 +249,1287 @@ pub struct Action {
     pub damage_type: Option<DamageType>,
     pub range: Option<String>,
     pub recharge: Option<String>,
+    pub action_type: ActionType,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionType {
+    Action,
+    BonusAction,
+    Reaction,
+    Legendary,
+}
+
+/// Tunable conversion balance, mirroring the frontend's ConversionSettings.
+/// Named presets ship with the app and are stored alongside custom profiles.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversionProfile {
+    pub name: String,
+    // Stat scale factor per Pokemon level (0.02 = full scaling at level 50)
+    pub level_multiplier: f32,
+    // Ability score points per point of scaled base stat
+    pub ability_score_scale: f32,
+    pub stat_scaling: StatScaling,
+    pub damage_types: DamageTypeMap,
+    #[serde(default)]
+    pub hit_point_method: HitPointMethod,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatScaling {
+    pub hp_multiplier: f32,
+    pub attack_multiplier: f32,
+    pub defense_multiplier: f32,
+    pub speed_multiplier: f32,
+    pub min_ability_score: u8,
+    pub max_ability_score: u8,
+}
+
+// Highest ability score 5e allows
+const MAX_ABILITY_SCORE: u8 = 30;
+
+impl ConversionProfile {
+    /// Reject settings the conversion cannot work with: ability scores outside
+    /// 1..=30 or the wrong way round, and multipliers that are not positive numbers
+    pub fn validate(&self) -> Result<(), String> {
+        let scaling = &self.stat_scaling;
+        let (min, max) = (scaling.min_ability_score, scaling.max_ability_score);
+        if min < 1 || max > MAX_ABILITY_SCORE || min > max {
+            return Err(format!(
+                "Ability scores must satisfy 1 <= min <= max <= {} (got {} to {})",
+                MAX_ABILITY_SCORE, min, max
+            ));
+        }
+
+        let multipliers = [
+            ("level_multiplier", self.level_multiplier),
+            ("ability_score_scale", self.ability_score_scale),
+            ("hp_multiplier", scaling.hp_multiplier),
+            ("attack_multiplier", scaling.attack_multiplier),
+            ("defense_multiplier", scaling.defense_multiplier),
+            ("speed_multiplier", scaling.speed_multiplier),
+        ];
+        for (name, value) in multipliers {
+            if !value.is_finite() || value <= 0.0 {
+                return Err(format!(
+                    "{} must be a positive number (got {})",
+                    name, value
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn presets() -> Vec<Self> {
+        vec![
+            Self::default(),
+            Self {
+                name: "gritty".to_string(),
+                ability_score_scale: 0.07,
+                stat_scaling: StatScaling {
+                    hp_multiplier: 0.75,
+                    attack_multiplier: 0.9,
+                    defense_multiplier: 1.0,
+                    speed_multiplier: 0.9,
+                    min_ability_score: 6,
+                    max_ability_score: 18,
+                },
+                ..Self::default()
+            },
+            Self {
+                name: "heroic".to_string(),
+                ability_score_scale: 0.09,
+                stat_scaling: StatScaling {
+                    hp_multiplier: 1.25,
+                    attack_multiplier: 1.1,
+                    defense_multiplier: 1.1,
+                    speed_multiplier: 1.1,
+                    min_ability_score: 10,
+                    max_ability_score: 24,
+                },
+                ..Self::default()
+            },
+        ]
+    }
+}
+
+impl Default for ConversionProfile {
+    // The "monster-manual" preset: the original hard-coded conversion
+    fn default() -> Self {
+        Self {
+            name: "monster-manual".to_string(),
+            level_multiplier: 0.02,
+            ability_score_scale: 0.08,
+            stat_scaling: StatScaling {
+                hp_multiplier: 1.0,
+                attack_multiplier: 1.0,
+                defense_multiplier: 1.0,
+                speed_multiplier: 1.0,
+                min_ability_score: 8,
+                max_ability_score: 20,
+            },
+            damage_types: DamageTypeMap::default(),
+            hit_point_method: HitPointMethod::Average,
+        }
+    }
 }
 
+// Number of moves a Pokemon can know at once, as in the games
+const MAX_KNOWN_MOVES: usize = 4;
+
 // Stat conversion logic
 impl DnDStatBlock {
-    pub fn from_pokemon(pokemon: &Pokemon, level: u8) -> Self {
-        let ability_scores = convert_base_stats_to_abilities(&pokemon.base_stats, level);
-        let hit_points = calculate_hit_points(&pokemon.base_stats, level);
-        let armor_class = calculate_armor_class(&pokemon.base_stats);
-        let speed = calculate_speed(&pokemon.base_stats);
+    pub fn from_pokemon(
+        pokemon: &Pokemon,
+        level: u8,
+        profile: &ConversionProfile,
+        type_chart: &TypeChart,
+    ) -> Self {
+        let damage_types = &profile.damage_types;
+        let size = CreatureSize::from_dimensions(pokemon.height, pokemon.weight);
+        let stats = apply_stat_scaling(&pokemon.base_stats, &profile.stat_scaling);
+        let ability_scores = convert_base_stats_to_abilities(&stats, level, profile);
+        let hit_dice = calculate_hit_dice(&ability_scores, level, size, profile);
+        let hit_points = match profile.hit_point_method {
+            HitPointMethod::Average => hit_dice.average(),
+            HitPointMethod::Roll => hit_dice.roll(),
+        };
+        let armor_class = calculate_armor_class(&stats, profile);
+        let speed = calculate_speed(&stats);
+        let carrying_capacity = calculate_carrying_capacity(&ability_scores, size);
+        let (resistances, vulnerabilities, immunities) =
+            convert_types_to_damage_modifiers(&pokemon.types, damage_types, type_chart);
+
+        // Attack bonuses depend on proficiency, which depends on CR, which depends on
+        // the attacks: rate the Pokemon with a proficiency from its defensive CR first,
+        // then build the final actions with the proficiency of the resulting CR.
+        // A lucky or unlucky roll doesn't change the CR; it is rated on average HP.
+        let defensive_cr = calculate_defensive_cr(
+            hit_dice.average(),
+            armor_class,
+            resistances.len() + immunities.len(),
+        );
+        let provisional_actions = convert_moves_to_actions(
+            &pokemon.moves,
+            &ability_scores,
+            proficiency_bonus_for_cr(defensive_cr),
+            level,
+            damage_types,
+        );
+        let challenge_rating =
+            calculate_challenge_rating(defensive_cr, calculate_offensive_cr(&provisional_actions));
+        let proficiency_bonus = proficiency_bonus_for_cr(challenge_rating);
+        let actions = convert_moves_to_actions(
+            &pokemon.moves,
+            &ability_scores,
+            proficiency_bonus,
+            level,
+            damage_types,
+        );
+        let skills = derive_skills(pokemon, &ability_scores, proficiency_bonus);
+        let saving_throws =
+            derive_saving_throws(&pokemon.base_stats, &ability_scores, proficiency_bonus);
 
         Self {
+            size,
             ability_scores,
             armor_class,
             hit_points,
+            hit_dice,
             speed,
-            skills: Vec::new(), // To be implemented
-            resistances: convert_types_to_resistances(&pokemon.types),
-            vulnerabilities: convert_types_to_vulnerabilities(&pokemon.types),
-            actions: Vec::new(), // To be implemented
-            challenge_rating: calculate_challenge_rating(&pokemon.base_stats, level),
+            carrying_capacity,
+            skills,
+            resistances,
+            vulnerabilities,
+            immunities,
+            actions,
+            challenge_rating,
+            proficiency_bonus,
+            saving_throws,
+            ability: None,
+            held_item: None,
         }
     }
+
+    /// Stat block for one caught Pokemon rather than its species: nature, IVs and EVs
+    /// adjust the stats, its chosen moves become the actions, and the ability (or the
+    /// species' first regular one) and held item are listed.
+    pub fn from_individual(
+        species: &Pokemon,
+        level: u8,
+        customization: &PokemonCustomization,
+        profile: &ConversionProfile,
+        type_chart: &TypeChart,
+    ) -> Self {
+        let individual = customization.apply_to(species);
+        let mut stat_block = Self::from_pokemon(&individual, level, profile, type_chart);
+
+        stat_block.ability = customization.ability.clone().or_else(|| {
+            species
+                .abilities
+                .iter()
+                .find(|a| !a.is_hidden)
+                .map(|a| a.name.clone())
+        });
+        stat_block.held_item = customization.held_item.clone();
+        stat_block
+    }
+}
+
+// Apply the profile's per-stat multipliers before any D&D conversion
+fn apply_stat_scaling(stats: &BaseStats, scaling: &StatScaling) -> BaseStats {
+    let scale = |stat: u32, multiplier: f32| (stat as f32 * multiplier).round() as u32;
+
+    BaseStats {
+        hp: stats.hp,
+        attack: scale(stats.attack, scaling.attack_multiplier),
+        defense: scale(stats.defense, scaling.defense_multiplier),
+        special_attack: scale(stats.special_attack, scaling.attack_multiplier),
+        special_defense: scale(stats.special_defense, scaling.defense_multiplier),
+        speed: scale(stats.speed, scaling.speed_multiplier),
+    }
 }
 
-fn convert_base_stats_to_abilities(stats: &BaseStats, level: u8) -> AbilityScores {
-    // Scale Pokemon stats (typically 1-255) to D&D ability scores (8-20)
-    let scale_factor = level as f32 / 50.0; // Adjust scaling based on level
+fn convert_base_stats_to_abilities(
+    stats: &BaseStats,
+    level: u8,
+    profile: &ConversionProfile,
+) -> AbilityScores {
+    // Scale Pokemon stats (typically 1-255) to D&D ability scores
+    let scale_factor = level as f32 * profile.level_multiplier; // Adjust scaling based on level
 
     AbilityScores {
-        strength: scale_stat(stats.attack, scale_factor),
-        dexterity: scale_stat(stats.speed, scale_factor),
-        constitution: scale_stat(stats.hp, scale_factor),
-        intelligence: scale_stat(stats.special_attack, scale_factor),
-        wisdom: scale_stat(stats.special_defense, scale_factor),
-        charisma: scale_stat((stats.attack + stats.special_attack) / 2, scale_factor),
+        strength: scale_stat(stats.attack, scale_factor, profile),
+        dexterity: scale_stat(stats.speed, scale_factor, profile),
+        constitution: scale_stat(stats.hp, scale_factor, profile),
+        intelligence: scale_stat(stats.special_attack, scale_factor, profile),
+        wisdom: scale_stat(stats.special_defense, scale_factor, profile),
+        charisma: scale_stat(
+            (stats.attack + stats.special_attack) / 2,
+            scale_factor,
+            profile,
+        ),
     }
 }
 
-fn scale_stat(base_stat: u32, scale_factor: f32) -> u8 {
-    let scaled = (base_stat as f32 * scale_factor * 0.08) + 8.0; // Base 8 + scaling
-    scaled.min(20.0).max(8.0) as u8
+fn scale_stat(base_stat: u32, scale_factor: f32, profile: &ConversionProfile) -> u8 {
+    let scaling = &profile.stat_scaling;
+    let min = scaling.min_ability_score as f32;
+    // An unvalidated profile may have min above max, which clamp() would panic on
+    let max = scaling.max_ability_score.max(scaling.min_ability_score) as f32;
+    // Minimum score + scaling
+    let scaled = (base_stat as f32 * scale_factor * profile.ability_score_scale) + min;
+    scaled.clamp(min, max) as u8
 }
 
-fn calculate_hit_points(stats: &BaseStats, level: u8) -> u32 {
-    let con_modifier = (scale_stat(stats.hp, level as f32 / 50.0) as i8 - 10) / 2;
-    let base_hp = level as u32 * 8; // d8 hit die
-    (base_hp as i32 + (con_modifier as i32 * level as i32)).max(1) as u32
+// One hit die (sized by the creature's size) per two levels, scaled by the profile's
+// HP multiplier, with the CON modifier added per die
+fn calculate_hit_dice(
+    abilities: &AbilityScores,
+    level: u8,
+    size: CreatureSize,
+    profile: &ConversionProfile,
+) -> HitDice {
+    let dice_for_level = (level as u32).div_ceil(2) as f32;
+    let count = ((dice_for_level * profile.stat_scaling.hp_multiplier).round() as u32).max(1);
+
+    HitDice {
+        count,
+        die: size.hit_die(),
+        modifier: count as i32 * ability_modifier(abilities.constitution) as i32,
+    }
 }
 
-fn calculate_armor_class(stats: &BaseStats) -> u8 {
+fn calculate_armor_class(stats: &BaseStats, profile: &ConversionProfile) -> u8 {
     let base_ac = 10;
-    let dex_modifier = (scale_stat(stats.speed, 1.0) as i8 - 10) / 2;
-    let natural_armor = (stats.defense / 20).min(5) as i8; // Natural armor bonus
+    let dex_modifier = (scale_stat(stats.speed, 1.0, profile) as i16 - 10) / 2;
+    let natural_armor = (stats.defense / 20).min(5) as i16; // Natural armor bonus
+
+    (base_ac + dex_modifier + natural_armor).clamp(10, u8::MAX as i16) as u8
+}
 
-    (base_ac + dex_modifier + natural_armor).max(10) as u8
+// Strength score x 15 lb., scaled by size
+fn calculate_carrying_capacity(abilities: &AbilityScores, size: CreatureSize) -> u32 {
+    (abilities.strength as f32 * 15.0 * size.carrying_multiplier()).round() as u32
 }
 
 fn calculate_speed(stats: &BaseStats) -> u32 {
     let base_speed = 30; // Standard D&D speed
-    let speed_bonus = (stats.speed / 10).min(20) as u32; // Max +20 speed
+    let speed_bonus = (stats.speed / 10).min(20); // Max +20 speed
     base_speed + speed_bonus
 }
 
-fn convert_types_to_resistances(types: &[crate::pokemon::PokemonType]) -> Vec<DamageType> {
-    // Simplified type effectiveness to resistances
+// Run every attacking type through the type chart against this Pokemon's types and
+// translate the result into 5e damage types. Several Pokemon types share a damage
+// type, so their multipliers are combined with a geometric mean: bludgeoning is
+// only resisted if the Pokemon shrugs off normal, fighting, ground, rock and water
+// hits on balance. A damage type is an immunity only when every source type is 0x.
+fn convert_types_to_damage_modifiers(
+    types: &[crate::pokemon::PokemonType],
+    damage_types: &DamageTypeMap,
+    type_chart: &TypeChart,
+) -> (Vec<DamageType>, Vec<DamageType>, Vec<DamageType>) {
+    let defending_types: Vec<&str> = types.iter().map(|t| t.name.as_str()).collect();
+
+    let mut multipliers: Vec<(DamageType, Vec<f32>)> = Vec::new();
+    for attacking in type_chart::POKEMON_TYPES {
+        let Some(damage_type) = damage_types.damage_type_for(attacking) else {
+            continue;
+        };
+        let multiplier = type_chart.defensive_multiplier(attacking, &defending_types);
+
+        match multipliers.iter_mut().find(|(t, _)| *t == damage_type) {
+            Some((_, values)) => values.push(multiplier),
+            None => multipliers.push((damage_type, vec![multiplier])),
+        }
+    }
+
     let mut resistances = Vec::new();
+    let mut vulnerabilities = Vec::new();
+    let mut immunities = Vec::new();
+
+    for (damage_type, values) in multipliers {
+        if values.iter().all(|m| *m == 0.0) {
+            immunities.push(damage_type);
+            continue;
+        }
+
+        // Treat a partial immunity as a strong (0.25x) resistance when averaging
+        let log_sum: f32 = values.iter().map(|m| m.max(0.25).ln()).sum();
+        let combined = (log_sum / values.len() as f32).exp();
+
+        if combined < 0.99 {
+            resistances.push(damage_type);
+        } else if combined > 1.01 {
+            vulnerabilities.push(damage_type);
+        }
+    }
+
+    (resistances, vulnerabilities, immunities)
+}
+
+// Dungeon Master's Guide "Monster Statistics by Challenge Rating":
+// (CR, armor class, max hit points, attack bonus, max damage per round)
+const MONSTER_STATISTICS: [(f32, u8, u32, i8, u32); 34] = [
+    (0.0, 13, 6, 3, 1),
+    (0.125, 13, 35, 3, 3),
+    (0.25, 13, 49, 3, 5),
+    (0.5, 13, 70, 3, 8),
+    (1.0, 13, 85, 3, 14),
+    (2.0, 13, 100, 3, 20),
+    (3.0, 13, 115, 4, 26),
+    (4.0, 14, 130, 5, 32),
+    (5.0, 15, 145, 6, 38),
+    (6.0, 15, 160, 6, 44),
+    (7.0, 15, 175, 6, 50),
+    (8.0, 16, 190, 7, 56),
+    (9.0, 16, 205, 7, 62),
+    (10.0, 17, 220, 7, 68),
+    (11.0, 17, 235, 8, 74),
+    (12.0, 17, 250, 8, 80),
+    (13.0, 18, 265, 8, 86),
+    (14.0, 18, 280, 8, 92),
+    (15.0, 18, 295, 8, 98),
+    (16.0, 18, 310, 9, 104),
+    (17.0, 19, 325, 10, 110),
+    (18.0, 19, 340, 10, 116),
+    (19.0, 19, 355, 10, 122),
+    (20.0, 19, 400, 10, 140),
+    (21.0, 19, 445, 11, 158),
+    (22.0, 19, 490, 11, 176),
+    (23.0, 19, 535, 11, 194),
+    (24.0, 19, 580, 12, 212),
+    (25.0, 19, 625, 12, 230),
+    (26.0, 19, 670, 12, 248),
+    (27.0, 19, 715, 13, 266),
+    (28.0, 19, 760, 13, 284),
+    (29.0, 19, 805, 13, 302),
+    (30.0, 19, 850, 14, 320),
+];
+
+// Row index into MONSTER_STATISTICS, which is how the DMG steps CR up and down
+fn cr_row_for(value: u32, column: impl Fn(&(f32, u8, u32, i8, u32)) -> u32) -> usize {
+    MONSTER_STATISTICS
+        .iter()
+        .position(|row| value <= column(row))
+        .unwrap_or(MONSTER_STATISTICS.len() - 1)
+}
+
+// Move a CR row by one step for every two points a stat is above or below expectation
+fn adjust_cr_row(row: usize, actual: i32, expected: i32) -> usize {
+    let steps = (actual - expected) / 2;
+    (row as i32 + steps).clamp(0, MONSTER_STATISTICS.len() as i32 - 1) as usize
+}
+
+fn calculate_defensive_cr(hit_points: u32, armor_class: u8, damage_modifier_count: usize) -> f32 {
+    // Creatures with three or more resistances/immunities are effectively tougher
+    let mut effective_hp = hit_points as f32;
+    if damage_modifier_count >= 3 {
+        let expected_cr = MONSTER_STATISTICS[cr_row_for(hit_points, |row| row.2)].0;
+        effective_hp *= match expected_cr {
+            cr if cr <= 4.0 => 2.0,
+            cr if cr <= 10.0 => 1.5,
+            cr if cr <= 16.0 => 1.25,
+            _ => 1.0,
+        };
+    }
+
+    let row = cr_row_for(effective_hp as u32, |row| row.2);
+    let expected_ac = MONSTER_STATISTICS[row].1;
+    let row = adjust_cr_row(row, armor_class as i32, expected_ac as i32);
+    MONSTER_STATISTICS[row].0
+}
+
+fn calculate_offensive_cr(actions: &[Action]) -> f32 {
+    // Rate the Pokemon by its hardest-hitting attack; area moves are assumed to hit two targets
+    let best_attack = actions
+        .iter()
+        .filter_map(|a| {
+            let dice = a.damage_dice.as_ref()?;
+            let targets = if a.range.as_deref() == Some("15-ft. radius") {
+                2.0
+            } else {
+                1.0
+            };
+            Some((average_damage(dice) * targets, a.attack_bonus.unwrap_or(0)))
+        })
+        .max_by(|a, b| a.0.total_cmp(&b.0));
+
+    let Some((damage_per_round, attack_bonus)) = best_attack else {
+        return 0.0;
+    };
+
+    let row = cr_row_for(damage_per_round.round() as u32, |row| row.4);
+    let expected_attack_bonus = MONSTER_STATISTICS[row].3;
+    let row = adjust_cr_row(row, attack_bonus as i32, expected_attack_bonus as i32);
+    MONSTER_STATISTICS[row].0
+}
+
+// Final CR is the average of defensive and offensive CR, snapped to a real CR value
+fn calculate_challenge_rating(defensive_cr: f32, offensive_cr: f32) -> f32 {
+    let average = (defensive_cr + offensive_cr) / 2.0;
+    MONSTER_STATISTICS
+        .iter()
+        .map(|row| row.0)
+        .min_by(|a, b| (a - average).abs().total_cmp(&(b - average).abs()))
+        .unwrap_or(0.0)
+}
+
+// Average roll of a damage expression such as "2d6 + 3"
+fn average_damage(damage_dice: &str) -> f32 {
+    let compact: String = damage_dice.chars().filter(|c| !c.is_whitespace()).collect();
+    let (dice, modifier) = match compact.find(['+', '-']) {
+        Some(i) => (&compact[..i], compact[i..].parse::<f32>().unwrap_or(0.0)),
+        None => (compact.as_str(), 0.0),
+    };
+
+    let dice_average = match dice.split_once('d') {
+        Some((count, size)) => {
+            let count = count.parse::<f32>().unwrap_or(1.0);
+            let size = size.parse::<f32>().unwrap_or(0.0);
+            count * (size + 1.0) / 2.0
+        }
+        None => dice.parse::<f32>().unwrap_or(0.0),
+    };
+
+    (dice_average + modifier).max(0.0)
+}
+
+pub fn ability_modifier(score: u8) -> i8 {
+    // Widened first: scores above 127 would wrap as i8
+    (score as i16 - 10).div_euclid(2) as i8
+}
 
-    for pokemon_type in types {
-        match pokemon_type.name.as_str() {
-            "fire" => resistances.push(DamageType::Fire),
-            "water" => resistances.push(DamageType::Water),
-            "grass" => resistances.push(DamageType::Grass),
-            "electric" => resistances.push(DamageType::Electric),
-            "steel" => {
-                resistances.push(DamageType::Steel);
-                resistances.push(DamageType::Poison);
+// 5e proficiency bonus by challenge rating (Monster Manual, "Proficiency Bonus by Challenge Rating")
+fn proficiency_bonus_for_cr(challenge_rating: f32) -> i8 {
+    match challenge_rating {
+        cr if cr < 5.0 => 2,
+        cr if cr < 9.0 => 3,
+        cr if cr < 13.0 => 4,
+        cr if cr < 17.0 => 5,
+        cr if cr < 21.0 => 6,
+        cr if cr < 25.0 => 7,
+        cr if cr < 29.0 => 8,
+        _ => 9,
+    }
+}
+
+// All six saves, proficient in the abilities behind the Pokemon's two strongest stats
+// (e.g. Special Defense -> WIS, Defense -> CON)
+fn derive_saving_throws(
+    stats: &BaseStats,
+    abilities: &AbilityScores,
+    proficiency_bonus: i8,
+) -> Vec<SavingThrow> {
+    let mut ranked = [
+        (stats.attack, "strength"),
+        (stats.speed, "dexterity"),
+        (stats.defense, "constitution"),
+        (stats.hp, "constitution"),
+        (stats.special_attack, "intelligence"),
+        (stats.special_defense, "wisdom"),
+    ];
+    ranked.sort_by_key(|(stat, _)| std::cmp::Reverse(*stat));
+
+    let mut proficient: Vec<&str> = Vec::new();
+    for (_, ability) in ranked {
+        if proficient.len() == 2 {
+            break;
+        }
+        if !proficient.contains(&ability) {
+            proficient.push(ability);
+        }
+    }
+
+    [
+        ("strength", abilities.strength),
+        ("dexterity", abilities.dexterity),
+        ("constitution", abilities.constitution),
+        ("intelligence", abilities.intelligence),
+        ("wisdom", abilities.wisdom),
+        ("charisma", abilities.charisma),
+    ]
+    .into_iter()
+    .map(|(ability, score)| {
+        let is_proficient = proficient.contains(&ability);
+        SavingThrow {
+            ability: ability.to_string(),
+            modifier: ability_modifier(score) + if is_proficient { proficiency_bonus } else { 0 },
+            proficient: is_proficient,
+        }
+    })
+    .collect()
+}
+
+// Proficient skills only, as listed in a 5e stat block. Strong stats and types grant
+// proficiency; an ability (e.g. Keen Eye) grants proficiency, or expertise when the
+// skill is already proficient from stats or types.
+fn derive_skills(
+    pokemon: &Pokemon,
+    abilities: &AbilityScores,
+    proficiency_bonus: i8,
+) -> Vec<Skill> {
+    let stats = &pokemon.base_stats;
+    let mut proficient: Vec<&'static str> = Vec::new();
+
+    if stats.speed >= 90 {
+        proficient.push("Acrobatics");
+    }
+    if stats.attack >= 90 {
+        proficient.push("Athletics");
+    }
+    if stats.special_attack >= 100 {
+        proficient.push("Arcana");
+    }
+    if stats.special_defense >= 90 {
+        proficient.push("Perception");
+    }
+
+    for pokemon_type in &pokemon.types {
+        proficient.extend(skills_for_type(&pokemon_type.name));
+    }
+
+    let mut expertise: Vec<&'static str> = Vec::new();
+    for ability in &pokemon.abilities {
+        if let Some(skill) = skill_for_ability(&ability.name) {
+            if proficient.contains(&skill) {
+                expertise.push(skill);
+            } else {
+                proficient.push(skill);
             }
-            _ => {}
         }
     }
 
-    resistances
+    proficient.sort_unstable();
+    proficient.dedup();
+
+    proficient
+        .into_iter()
+        .map(|name| {
+            let has_expertise = expertise.contains(&name);
+            let multiplier = if has_expertise { 2 } else { 1 };
+            Skill {
+                name: name.to_string(),
+                modifier: ability_modifier(skill_ability_score(name, abilities))
+                    + proficiency_bonus * multiplier,
+                proficient: true,
+                expertise: has_expertise,
+            }
+        })
+        .collect()
 }
 
-fn convert_types_to_vulnerabilities(types: &[crate::pokemon::PokemonType]) -> Vec<DamageType> {
-    let mut vulnerabilities = Vec::new();
+fn skills_for_type(pokemon_type: &str) -> &'static [&'static str] {
+    match pokemon_type {
+        "psychic" => &["Insight", "Arcana"],
+        "ghost" => &["Stealth", "Deception"],
+        "dark" => &["Stealth", "Deception"],
+        "fairy" => &["Persuasion", "Performance"],
+        "fighting" => &["Athletics", "Intimidation"],
+        "flying" => &["Perception", "Acrobatics"],
+        "bug" => &["Survival", "Stealth"],
+        "grass" => &["Nature", "Survival"],
+        "ground" => &["Survival", "Athletics"],
+        "dragon" => &["Intimidation", "Arcana"],
+        "fire" => &["Intimidation"],
+        "water" => &["Athletics"],
+        "electric" => &["Acrobatics"],
+        "ice" => &["Survival"],
+        "rock" => &["Athletics"],
+        "poison" => &["Nature"],
+        "steel" => &["Investigation"],
+        _ => &[],
+    }
+}
+
+fn skill_for_ability(ability: &str) -> Option<&'static str> {
+    match ability {
+        "keen-eye" | "compound-eyes" | "frisk" | "vital-spirit" => Some("Perception"),
+        "anticipation" | "forewarn" | "synchronize" | "telepathy" => Some("Insight"),
+        "intimidate" | "pressure" => Some("Intimidation"),
+        "pickup" | "honey-gather" => Some("Investigation"),
+        "sand-veil" | "snow-cloak" | "infiltrator" | "illusion" => Some("Stealth"),
+        "pickpocket" | "magician" => Some("Sleight of Hand"),
+        "cute-charm" | "friend-guard" => Some("Persuasion"),
+        "run-away" | "quick-feet" | "speed-boost" => Some("Acrobatics"),
+        "swift-swim" | "guts" | "huge-power" => Some("Athletics"),
+        "healer" | "natural-cure" => Some("Medicine"),
+        "leaf-guard" | "chlorophyll" => Some("Nature"),
+        _ => None,
+    }
+}
+
+fn skill_ability_score(skill: &str, abilities: &AbilityScores) -> u8 {
+    match skill {
+        "Athletics" => abilities.strength,
+        "Acrobatics" | "Sleight of Hand" | "Stealth" => abilities.dexterity,
+        "Arcana" | "History" | "Investigation" | "Nature" | "Religion" => abilities.intelligence,
+        "Animal Handling" | "Insight" | "Medicine" | "Perception" | "Survival" => abilities.wisdom,
+        _ => abilities.charisma, // Deception, Intimidation, Performance, Persuasion
+    }
+}
+
+fn convert_moves_to_actions(
+    moves: &[Move],
+    abilities: &AbilityScores,
+    proficiency_bonus: i8,
+    level: u8,
+    damage_types: &DamageTypeMap,
+) -> Vec<Action> {
+    // Like the games, a Pokemon knows the most recent moves it has learned by level
+    let known: Vec<&Move> = moves
+        .iter()
+        .filter(|m| m.level_learned_at <= level as u32)
+        .collect();
+
+    known
+        .iter()
+        .skip(known.len().saturating_sub(MAX_KNOWN_MOVES))
+        .map(|m| convert_move_to_action(m, abilities, proficiency_bonus, damage_types))
+        .collect()
+}
+
+fn convert_move_to_action(
+    pokemon_move: &Move,
+    abilities: &AbilityScores,
+    proficiency_bonus: i8,
+    damage_types: &DamageTypeMap,
+) -> Action {
+    let name = format_move_name(&pokemon_move.name);
+    let damage_type = damage_types.damage_type_for(&pokemon_move.move_type);
+    let action_type = match (pokemon_move.priority, pokemon_move.damage_class.as_str()) {
+        (p, "status") if p > 0 => ActionType::Reaction,
+        (p, _) if p > 0 => ActionType::BonusAction,
+        _ => ActionType::Action,
+    };
+    let effect = pokemon_move.effect.clone().unwrap_or_default();
+    // Hyper Beam and friends cost the user its next turn, which 5e models as a recharge
+    let recharge = if effect.to_lowercase().contains("recharge") {
+        Some("5-6".to_string())
+    } else {
+        None
+    };
+
+    if pokemon_move.damage_class == "status" {
+        let save_dc = 8 + proficiency_bonus + ability_modifier(abilities.charisma);
+        let description = if targets_self_or_allies(&pokemon_move.target) {
+            effect
+        } else {
+            let save = format!(
+                "Each target must make a DC {} {} saving throw.",
+                save_dc,
+                save_ability_for_type(&pokemon_move.move_type)
+            );
+            if effect.is_empty() {
+                format!(
+                    "{} On a failure, the target suffers the effect of {}.",
+                    save, name
+                )
+            } else {
+                format!("{} On a failure: {}", save, effect)
+            }
+        };
+
+        return Action {
+            name,
+            description,
+            attack_bonus: None,
+            damage_dice: None,
+            damage_type: None,
+            range: Some(move_range(pokemon_move)),
+            recharge,
+            action_type,
+        };
+    }
+
+    // Physical moves are driven by STR (Attack), special moves by INT (Special Attack)
+    let ability_mod = if pokemon_move.damage_class == "physical" {
+        ability_modifier(abilities.strength)
+    } else {
+        ability_modifier(abilities.intelligence)
+    };
+    let attack_bonus = ability_mod + proficiency_bonus;
+    let (dice_count, die_size) = damage_dice_for_power(pokemon_move.power);
+    let average_damage =
+        (dice_count as i32 * (die_size as i32 + 1) / 2 + ability_mod as i32).max(1);
+    let damage_dice = match ability_mod {
+        0 => format!("{}d{}", dice_count, die_size),
+        m if m > 0 => format!("{}d{} + {}", dice_count, die_size, m),
+        m => format!("{}d{} - {}", dice_count, die_size, -m),
+    };
+    let damage_text = match &damage_type {
+        Some(t) => format!("{} damage", t),
+        None => "damage".to_string(),
+    };
+    let attack_kind = if pokemon_move.damage_class == "physical" {
+        "Melee Weapon Attack"
+    } else {
+        "Ranged Spell Attack"
+    };
+
+    let mut description = format!(
+        "{}: {:+} to hit, {}. Hit: {} ({}) {}.",
+        attack_kind,
+        attack_bonus,
+        move_range(pokemon_move),
+        average_damage,
+        damage_dice,
+        damage_text
+    );
+    if !effect.is_empty() {
+        description.push(' ');
+        description.push_str(&effect);
+    }
+
+    Action {
+        name,
+        description,
+        attack_bonus: Some(attack_bonus),
+        damage_dice: Some(damage_dice),
+        damage_type,
+        range: Some(move_range(pokemon_move)),
+        recharge,
+        action_type,
+    }
+}
+
+// Map move power onto 5e damage dice, from a cantrip-sized 1d6 up to a 4d10 nuke
+fn damage_dice_for_power(power: Option<u32>) -> (u8, u8) {
+    match power {
+        None => (1, 8), // Fixed or variable damage moves
+        Some(p) if p <= 30 => (1, 6),
+        Some(p) if p <= 50 => (1, 8),
+        Some(p) if p <= 70 => (2, 6),
+        Some(p) if p <= 90 => (2, 8),
+        Some(p) if p <= 110 => (2, 10),
+        Some(p) if p <= 130 => (3, 10),
+        Some(_) => (4, 10),
+    }
+}
+
+fn move_range(pokemon_move: &Move) -> String {
+    match pokemon_move.target.as_str() {
+        "user" | "users-field" | "user-and-allies" | "ally" | "user-or-ally" => "Self".to_string(),
+        "all-opponents" | "all-other-pokemon" | "entire-field" | "opponents-field" => {
+            "15-ft. radius".to_string()
+        }
+        _ if pokemon_move.damage_class == "physical" => "reach 5 ft., one target".to_string(),
+        _ => "range 60 ft., one target".to_string(),
+    }
+}
+
+fn targets_self_or_allies(target: &str) -> bool {
+    matches!(
+        target,
+        "user" | "users-field" | "user-and-allies" | "ally" | "user-or-ally" | "entire-field"
+    )
+}
 
-    for pokemon_type in types {
-        match pokemon_type.name.as_str() {
-            "fire" => vulnerabilities.push(DamageType::Water),
-            "water" => vulnerabilities.push(DamageType::Electric),
-            "grass" => vulnerabilities.push(DamageType::Fire),
-            "ice" => vulnerabilities.push(DamageType::Fire),
-            _ => {}
+fn save_ability_for_type(move_type: &str) -> &'static str {
+    match move_type {
+        "poison" | "grass" | "bug" => "Constitution",
+        "electric" | "ice" | "ground" | "rock" | "water" | "fire" | "flying" | "steel" => {
+            "Dexterity"
         }
+        _ => "Wisdom",
     }
+}
 
-    vulnerabilities
+// "thunder-punch" -> "Thunder Punch"
+fn format_move_name(name: &str) -> String {
+    name.split('-')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
 }
 
-fn calculate_challenge_rating(stats: &BaseStats, level: u8) -> f32 {
-    let total_stats: u32 = stats.hp
-        + stats.attack
-        + stats.defense
-        + stats.special_attack
-        + stats.special_defense
-        + stats.speed;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{bulbasaur, level_up_move, snorlax};
+
+    fn stat_block(pokemon: &Pokemon, level: u8) -> DnDStatBlock {
+        DnDStatBlock::from_pokemon(
+            pokemon,
+            level,
+            &ConversionProfile::default(),
+            &TypeChart::default(),
+        )
+    }
+
+    #[test]
+    fn moves_become_attacks_and_saves() {
+        let mut pokemon = snorlax();
+        let mut yawn = level_up_move("yawn", "normal", "status", None, 1);
+        yawn.effect = Some("The target falls asleep at the end of the next turn.".to_string());
+        pokemon.moves.push(yawn);
+        let stat_block = stat_block(&pokemon, 50);
+
+        let proficiency = proficiency_bonus_for_cr(stat_block.challenge_rating);
+        let body_slam = &stat_block.actions[0];
+        assert_eq!(body_slam.name, "Body Slam");
+        assert_eq!(
+            body_slam.attack_bonus,
+            Some(ability_modifier(stat_block.ability_scores.strength) + proficiency)
+        );
+        assert!(body_slam.damage_dice.as_deref().unwrap().starts_with("2d8"));
+        assert_eq!(body_slam.range.as_deref(), Some("reach 5 ft., one target"));
+
+        let yawn = &stat_block.actions[1];
+        assert_eq!(yawn.attack_bonus, None);
+        assert_eq!(yawn.damage_dice, None);
+        assert!(yawn.description.contains("saving throw"));
+        assert!(yawn.description.ends_with("next turn."));
+    }
+
+    #[test]
+    fn only_moves_that_recharge_get_a_recharge() {
+        let mut pokemon = snorlax();
+        let mut hyper_beam = level_up_move("hyper-beam", "normal", "special", Some(150), 1);
+        hyper_beam.pp = 5;
+        hyper_beam.effect = Some("User foregoes its next turn to recharge.".to_string());
+        let mut focus_punch = level_up_move("focus-punch", "fighting", "physical", Some(150), 1);
+        focus_punch.pp = 5;
+        let mut glare = level_up_move("glare", "normal", "status", None, 1);
+        glare.pp = 30;
+        pokemon.moves = vec![hyper_beam, focus_punch, glare];
+        let stat_block = stat_block(&pokemon, 50);
+
+        assert_eq!(stat_block.actions[0].recharge.as_deref(), Some("5-6"));
+        assert_eq!(stat_block.actions[1].recharge, None);
+        let glare = &stat_block.actions[2];
+        assert!(glare.description.ends_with("suffers the effect of Glare."));
+    }
+
+    #[test]
+    fn only_the_latest_four_moves_are_known() {
+        let mut pokemon = snorlax();
+        pokemon.moves = ["tackle", "defense-curl", "amnesia", "lick", "headbutt"]
+            .iter()
+            .zip(1..)
+            .map(|(name, level)| level_up_move(name, "normal", "physical", Some(40), level * 5))
+            .collect();
+
+        let names = |level| -> Vec<String> {
+            stat_block(&pokemon, level)
+                .actions
+                .into_iter()
+                .map(|a| a.name)
+                .collect()
+        };
+        assert_eq!(names(10), vec!["Tackle", "Defense Curl"]);
+        assert_eq!(
+            names(25),
+            vec!["Defense Curl", "Amnesia", "Lick", "Headbutt"]
+        );
+    }
+
+    #[test]
+    fn damage_types_follow_the_map() {
+        let map = DamageTypeMap::default();
+        assert_eq!(map.damage_type_for("electric"), Some(DamageType::Lightning));
+        assert_eq!(map.damage_type_for("fairy"), Some(DamageType::Radiant));
+        assert_eq!(map.damage_type_for("shadow"), None);
+        assert_eq!(map.mappings.len(), 18);
+
+        let stat_block = stat_block(&snorlax(), 20);
+        assert_eq!(
+            stat_block.actions[0].damage_type,
+            Some(DamageType::Bludgeoning)
+        );
+        assert!(stat_block.actions[0]
+            .description
+            .contains("bludgeoning damage"));
+    }
+
+    #[test]
+    fn damage_modifiers_follow_the_type_chart() {
+        let mut pokemon = snorlax();
+        pokemon.types = vec![crate::pokemon::PokemonType {
+            name: "ground".to_string(),
+            slot: 1,
+        }];
+        let stat_block = stat_block(&pokemon, 30);
+
+        assert_eq!(stat_block.immunities, vec![DamageType::Lightning]);
+        assert!(stat_block.vulnerabilities.contains(&DamageType::Cold));
+        assert!(stat_block.resistances.contains(&DamageType::Poison));
+        assert!(!stat_block.resistances.contains(&DamageType::Lightning));
+    }
+
+    #[test]
+    fn skills_come_from_stats_types_and_abilities() {
+        let mut pokemon = snorlax();
+        pokemon.base_stats.speed = 101;
+        pokemon.types = vec![crate::pokemon::PokemonType {
+            name: "flying".to_string(),
+            slot: 1,
+        }];
+        pokemon.abilities = vec![crate::pokemon::Ability {
+            name: "keen-eye".to_string(),
+            is_hidden: false,
+            slot: 1,
+        }];
+        let stat_block = stat_block(&pokemon, 40);
+        let skill = |name: &str| stat_block.skills.iter().find(|s| s.name == name);
+
+        // Flying grants Perception, so Keen Eye makes it expertise
+        let perception = skill("Perception").unwrap();
+        assert!(perception.expertise);
+        let acrobatics = skill("Acrobatics").unwrap();
+        assert!(acrobatics.proficient && !acrobatics.expertise);
+        assert!(skill("Athletics").is_some());
+        assert!(skill("Stealth").is_none());
+        assert!(perception.modifier > acrobatics.modifier - 10);
+    }
+
+    #[test]
+    fn proficiency_follows_challenge_rating() {
+        assert_eq!(proficiency_bonus_for_cr(0.0), 2);
+        assert_eq!(proficiency_bonus_for_cr(4.0), 2);
+        assert_eq!(proficiency_bonus_for_cr(5.0), 3);
+        assert_eq!(proficiency_bonus_for_cr(13.0), 5);
+        assert_eq!(proficiency_bonus_for_cr(24.0), 7);
+        assert_eq!(proficiency_bonus_for_cr(30.0), 9);
+    }
+
+    #[test]
+    fn saves_follow_the_strongest_stats() {
+        // Snorlax's HP then Attack lead its stats
+        let stat_block = stat_block(&snorlax(), 50);
+        assert_eq!(stat_block.saving_throws.len(), 6);
+        let proficient: Vec<&str> = stat_block
+            .saving_throws
+            .iter()
+            .filter(|s| s.proficient)
+            .map(|s| s.ability.as_str())
+            .collect();
+        assert_eq!(proficient, vec!["strength", "constitution"]);
+
+        let strength = &stat_block.saving_throws[0];
+        assert_eq!(
+            strength.modifier,
+            ability_modifier(stat_block.ability_scores.strength) + stat_block.proficiency_bonus
+        );
+        assert_eq!(
+            stat_block.proficiency_bonus,
+            proficiency_bonus_for_cr(stat_block.challenge_rating)
+        );
+    }
+
+    #[test]
+    fn monster_statistics_rise_with_challenge_rating() {
+        for pair in MONSTER_STATISTICS.windows(2) {
+            let (lower, higher) = (pair[0], pair[1]);
+            assert!(lower.0 < higher.0);
+            assert!(lower.1 <= higher.1);
+            assert!(lower.2 < higher.2);
+            assert!(lower.3 <= higher.3);
+            assert!(lower.4 < higher.4);
+        }
+    }
+
+    #[test]
+    fn challenge_rating_rows_and_adjustments() {
+        assert_eq!(cr_row_for(1, |row| row.2), 0);
+        assert_eq!(MONSTER_STATISTICS[cr_row_for(100, |row| row.2)].0, 2.0);
+        assert_eq!(MONSTER_STATISTICS[cr_row_for(101, |row| row.2)].0, 3.0);
+        assert_eq!(
+            cr_row_for(10_000, |row| row.2),
+            MONSTER_STATISTICS.len() - 1
+        );
+
+        // AC 13 is expected at CR 2; 17 is two steps up
+        assert_eq!(calculate_defensive_cr(100, 13, 0), 2.0);
+        assert_eq!(calculate_defensive_cr(100, 17, 0), 4.0);
+        // Three or more resistances double effective HP at low CR
+        assert_eq!(calculate_defensive_cr(50, 13, 3), 2.0);
+
+        assert_eq!(calculate_challenge_rating(2.0, 4.0), 3.0);
+        assert_eq!(calculate_challenge_rating(0.0, 0.25), 0.125);
+    }
+
+    #[test]
+    fn damage_expressions_average() {
+        assert_eq!(average_damage("2d6 + 3"), 10.0);
+        assert_eq!(average_damage("1d8"), 4.5);
+        assert_eq!(average_damage("1d4 - 5"), 0.0);
+        assert_eq!(average_damage("7"), 7.0);
+    }
+
+    #[test]
+    fn presets_are_named() {
+        let names: Vec<String> = ConversionProfile::presets()
+            .into_iter()
+            .map(|p| p.name)
+            .collect();
+        assert_eq!(names, vec!["monster-manual", "gritty", "heroic"]);
+    }
+
+    #[test]
+    fn stat_blocks_stay_within_the_profile() {
+        let pokemon = snorlax();
+        for profile in ConversionProfile::presets() {
+            let scaling = &profile.stat_scaling;
+            for level in [1, 50, 100] {
+                let stat_block =
+                    DnDStatBlock::from_pokemon(&pokemon, level, &profile, &TypeChart::default());
+                let scores = &stat_block.ability_scores;
+                for score in [
+                    scores.strength,
+                    scores.dexterity,
+                    scores.constitution,
+                    scores.intelligence,
+                    scores.wisdom,
+                    scores.charisma,
+                ] {
+                    assert!(
+                        (scaling.min_ability_score..=scaling.max_ability_score).contains(&score)
+                    );
+                }
+                assert_eq!(
+                    stat_block.proficiency_bonus,
+                    proficiency_bonus_for_cr(stat_block.challenge_rating)
+                );
+                assert_eq!(stat_block.actions.len(), 1);
+            }
+        }
+    }
+
+    #[test]
+    fn higher_levels_are_never_weaker() {
+        let pokemon = snorlax();
+        let low = stat_block(&pokemon, 10);
+        let high = stat_block(&pokemon, 80);
+        assert!(high.hit_points > low.hit_points);
+        assert!(high.ability_scores.strength >= low.ability_scores.strength);
+        assert!(high.challenge_rating >= low.challenge_rating);
+    }
+
+    #[test]
+    fn individuals_list_their_ability_and_item() {
+        let species = bulbasaur();
+        let profile = ConversionProfile::default();
+
+        let plain = DnDStatBlock::from_individual(
+            &species,
+            10,
+            &Default::default(),
+            &profile,
+            &TypeChart::default(),
+        );
+        assert_eq!(plain.ability.as_deref(), Some("overgrow"));
+        assert_eq!(plain.held_item, None);
+
+        let customization = PokemonCustomization {
+            ability: Some("chlorophyll".to_string()),
+            held_item: Some("miracle-seed".to_string()),
+            moves: vec!["vine-whip".to_string()],
+            ..Default::default()
+        };
+        let individual = DnDStatBlock::from_individual(
+            &species,
+            10,
+            &customization,
+            &profile,
+            &TypeChart::default(),
+        );
+        assert_eq!(individual.ability.as_deref(), Some("chlorophyll"));
+        assert_eq!(individual.held_item.as_deref(), Some("miracle-seed"));
+        let actions: Vec<&str> = individual.actions.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(actions, vec!["Vine Whip"]);
+    }
+
+    #[test]
+    fn sizes_come_from_height_then_weight() {
+        assert_eq!(CreatureSize::from_dimensions(4, 60), CreatureSize::Tiny);
+        assert_eq!(CreatureSize::from_dimensions(7, 69), CreatureSize::Small);
+        assert_eq!(CreatureSize::from_dimensions(21, 4600), CreatureSize::Large);
+        assert_eq!(
+            CreatureSize::from_dimensions(145, 3980),
+            CreatureSize::Gargantuan
+        );
+        assert_eq!(CreatureSize::from_dimensions(0, 0), CreatureSize::Medium);
+    }
+
+    #[test]
+    fn size_sets_hit_die_and_carrying_capacity() {
+        // Snorlax is tall enough for Medium but too heavy for it
+        let stat_block = stat_block(&snorlax(), 50);
+        assert_eq!(stat_block.size, CreatureSize::Large);
+        assert_eq!(CreatureSize::Large.hit_die(), 10);
+        assert_eq!(
+            stat_block.carrying_capacity,
+            stat_block.ability_scores.strength as u32 * 30
+        );
+    }
+
+    #[test]
+    fn hit_dice_average_rounds_down_with_a_floor_of_one() {
+        let dice = HitDice {
+            count: 3,
+            die: 8,
+            modifier: 3,
+        };
+        assert_eq!(dice.average(), 16);
+        assert_eq!(dice.to_string(), "3d8 + 3");
+
+        let dice = HitDice {
+            count: 1,
+            die: 4,
+            modifier: -5,
+        };
+        assert_eq!(dice.average(), 1);
+        assert_eq!(dice.to_string(), "1d4 - 5");
+        assert!(dice.roll() >= 1);
+    }
+
+    #[test]
+    fn hit_points_come_from_the_hit_dice() {
+        let pokemon = snorlax();
+        let stat_block = stat_block(&pokemon, 50);
+        assert_eq!(stat_block.hit_dice.die, 10);
+        assert_eq!(stat_block.hit_dice.count, 25);
+        assert_eq!(stat_block.hit_points, stat_block.hit_dice.average());
+
+        let rolling = ConversionProfile {
+            hit_point_method: HitPointMethod::Roll,
+            ..Default::default()
+        };
+        let rolled = DnDStatBlock::from_pokemon(&pokemon, 50, &rolling, &TypeChart::default());
+        let dice = &rolled.hit_dice;
+        let lowest = (dice.count as i32 + dice.modifier).max(1) as u32;
+        let highest = (dice.count * dice.die) as i32 + dice.modifier;
+        assert!((lowest..=highest as u32).contains(&rolled.hit_points));
+    }
+
+    #[test]
+    fn stat_blocks_serialize_in_camel_case() {
+        let json = serde_json::to_value(stat_block(&snorlax(), 30)).unwrap();
+        for field in [
+            "abilityScores",
+            "armorClass",
+            "hitPoints",
+            "hitDice",
+            "carryingCapacity",
+            "challengeRating",
+            "proficiencyBonus",
+            "savingThrows",
+            "heldItem",
+        ] {
+            assert!(json.get(field).is_some(), "missing {}", field);
+        }
+        assert!(json["actions"][0].get("actionType").is_some());
+        assert!(json.get("armor_class").is_none());
+    }
+
+    #[test]
+    fn ability_modifiers_round_down() {
+        assert_eq!(ability_modifier(1), -5);
+        assert_eq!(ability_modifier(9), -1);
+        assert_eq!(ability_modifier(10), 0);
+        assert_eq!(ability_modifier(11), 0);
+        assert_eq!(ability_modifier(20), 5);
+        assert_eq!(ability_modifier(30), 10);
+        assert_eq!(ability_modifier(255), 122);
+    }
+
+    #[test]
+    fn profiles_are_validated() {
+        for preset in ConversionProfile::presets() {
+            assert_eq!(preset.validate(), Ok(()), "{}", preset.name);
+        }
+
+        let mut profile = ConversionProfile::default();
+        profile.stat_scaling.min_ability_score = 0;
+        assert!(profile.validate().is_err());
 
-    let base_cr = (total_stats as f32 / 600.0) * (level as f32 / 50.0);
-    (base_cr * 4.0).round() / 4.0 // Round to nearest quarter
+        let mut profile = ConversionProfile::default();
+        profile.stat_scaling.max_ability_score = 31;
+        assert!(profile.validate().is_err());
+
+        let mut profile = ConversionProfile::default();
+        profile.stat_scaling.min_ability_score = 20;
+        profile.stat_scaling.max_ability_score = 10;
+        assert!(profile.validate().is_err());
+
+        let profile = ConversionProfile {
+            level_multiplier: 0.0,
+            ..Default::default()
+        };
+        assert!(profile.validate().is_err());
+
+        let mut profile = ConversionProfile::default();
+        profile.stat_scaling.hp_multiplier = f32::NAN;
+        assert!(profile.validate().is_err());
+    }
+
+    #[test]
+    fn profiles_serialize_in_camel_case() {
+        let json = serde_json::to_value(ConversionProfile::default()).unwrap();
+        assert!(json.get("levelMultiplier").is_some());
+        assert!(json.get("hitPointMethod").is_some());
+        assert!(json["statScaling"].get("minAbilityScore").is_some());
+        assert!(json.get("stat_scaling").is_none());
+    }
+
+    #[test]
+    fn unvalidated_profiles_do_not_panic() {
+        let mut profile = ConversionProfile::default();
+        profile.stat_scaling.min_ability_score = 200;
+        profile.stat_scaling.max_ability_score = 10;
+        let stat_block =
+            DnDStatBlock::from_pokemon(&snorlax(), 50, &profile, &TypeChart::default());
+        assert!(stat_block.armor_class >= 10);
+    }
 }
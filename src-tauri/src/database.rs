@@ -1,6 +1,18 @@
-use crate::pokemon::Pokemon;
+use crate::converter::{ConversionProfile, DnDStatBlock};
+use crate::loader::{FailedLoad, LoadReport};
+use crate::migrations;
+use crate::pokemon::{
+    generation_for_national_dex_id, DexEntry, EvolutionChain, Generation, Move, Pokedex, Pokemon,
+    PokemonCustomization, Species,
+};
+use crate::type_chart::TypeChart;
 use serde::{Deserialize, Serialize};
-use sqlx::{sqlite::SqlitePool, Row};
+use sqlx::{
+    sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions, SqliteRow},
+    Row, SqliteConnection,
+};
+use std::fmt;
+use std::path::Path;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserPokemon {
@@ -12,6 +24,8 @@ pub struct UserPokemon {
     pub captured_date: String,
     pub is_shiny: bool,
     pub trainer_id: i64,
+    pub location: Option<StorageLocation>,
+    pub customization: PokemonCustomization,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,59 +35,216 @@ pub struct Trainer {
     pub created_date: String,
 }
 
+pub const PARTY_SIZE: u8 = 6;
+pub const BOX_CAPACITY: u32 = 30;
+// Level the stored challenge ratings are worked out at
+pub const CHALLENGE_RATING_LEVEL: u8 = 50;
+
+/// Where an owned Pokemon is kept: a party slot (1-6) or a position (1-30) in a box
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StorageLocation {
+    Party { slot: u8 },
+    Box { box_id: i64, position: u32 },
+}
+
+impl StorageLocation {
+    // (party_slot, box_id, box_position) column values
+    fn columns(&self) -> (Option<u8>, Option<i64>, Option<u32>) {
+        match *self {
+            StorageLocation::Party { slot } => (Some(slot), None, None),
+            StorageLocation::Box { box_id, position } => (None, Some(box_id), Some(position)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PokemonBox {
+    pub id: i64,
+    pub trainer_id: i64,
+    pub name: String,
+    pub position: u32,
+}
+
+/// Party and box operations fail either in SQLite or on a storage rule
+#[derive(Debug)]
+pub enum StorageError {
+    Database(sqlx::Error),
+    PokemonNotFound(i64),
+    BoxNotFound(i64),
+    PartyFull,
+    LastPartyMember,
+    BoxFull(i64),
+    BoxNotEmpty(i64),
+    PositionTaken { box_id: i64, position: u32 },
+    InvalidLocation(String),
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageError::Database(e) => write!(f, "Database error: {}", e),
+            StorageError::PokemonNotFound(id) => write!(f, "Owned Pokemon {} not found", id),
+            StorageError::BoxNotFound(id) => write!(f, "Box {} not found", id),
+            StorageError::PartyFull => {
+                write!(f, "The party already has {} Pokemon", PARTY_SIZE)
+            }
+            StorageError::LastPartyMember => {
+                write!(f, "The last Pokemon in the party cannot be deposited")
+            }
+            StorageError::BoxFull(id) => write!(f, "Box {} is full", id),
+            StorageError::BoxNotEmpty(id) => write!(f, "Box {} still holds Pokemon", id),
+            StorageError::PositionTaken { box_id, position } => {
+                write!(
+                    f,
+                    "Position {} in box {} is already taken",
+                    position, box_id
+                )
+            }
+            StorageError::InvalidLocation(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+impl From<sqlx::Error> for StorageError {
+    fn from(e: sqlx::Error) -> Self {
+        StorageError::Database(e)
+    }
+}
+
+/// Structured Pokedex query. Every field is optional; an empty query matches everything.
+/// The challenge rating range is checked against the default profile at
+/// `CHALLENGE_RATING_LEVEL`, as stored with each cached Pokemon.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PokemonQuery {
+    pub types: Vec<String>,
+    // Require every listed type rather than any of them
+    pub match_all_types: bool,
+    pub generations: Vec<u32>,
+    // Only species listed in this Pokedex, by name (e.g. "kanto", "paldea")
+    pub pokedex: Option<String>,
+    pub stat_ranges: Vec<StatRange>,
+    pub min_base_stat_total: Option<u32>,
+    pub max_base_stat_total: Option<u32>,
+    pub ability: Option<String>,
+    pub search_query: Option<String>,
+    pub min_challenge_rating: Option<f32>,
+    pub max_challenge_rating: Option<f32>,
+    pub sort_by: PokemonSortField,
+    pub descending: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatRange {
+    pub stat: PokemonStat,
+    pub min: Option<u32>,
+    pub max: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PokemonStat {
+    Hp,
+    Attack,
+    Defense,
+    SpecialAttack,
+    SpecialDefense,
+    Speed,
+}
+
+impl PokemonStat {
+    fn column(&self) -> &'static str {
+        match self {
+            PokemonStat::Hp => "hp",
+            PokemonStat::Attack => "attack",
+            PokemonStat::Defense => "defense",
+            PokemonStat::SpecialAttack => "special_attack",
+            PokemonStat::SpecialDefense => "special_defense",
+            PokemonStat::Speed => "speed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PokemonSortField {
+    #[default]
+    Id,
+    Name,
+    Hp,
+    Attack,
+    Defense,
+    SpecialAttack,
+    SpecialDefense,
+    Speed,
+    BaseStatTotal,
+    Generation,
+}
+
+impl PokemonSortField {
+    fn column(&self) -> &'static str {
+        match self {
+            PokemonSortField::Id => "id",
+            PokemonSortField::Name => "name",
+            PokemonSortField::Hp => "hp",
+            PokemonSortField::Attack => "attack",
+            PokemonSortField::Defense => "defense",
+            PokemonSortField::SpecialAttack => "special_attack",
+            PokemonSortField::SpecialDefense => "special_defense",
+            PokemonSortField::Speed => "speed",
+            PokemonSortField::BaseStatTotal => "base_stat_total",
+            PokemonSortField::Generation => "generation",
+        }
+    }
+}
+
+/// Whether the app is keeping its data, so the frontend can warn when it is not
+#[derive(Debug, Clone, Serialize)]
+pub struct DatabaseStatus {
+    pub persistent: bool,
+    // Why the database file could not be used
+    pub error: Option<String>,
+}
+
+#[derive(Clone)]
 pub struct Database {
     pool: SqlitePool,
 }
 
 impl Database {
-    pub async fn new(database_url: &str) -> Result<Self, sqlx::Error> {
-        let pool = SqlitePool::connect(database_url).await?;
+    /// A database that lives only as long as the app. Every SQLite in-memory connection
+    /// is a separate database, so the pool holds exactly one and never recycles it.
+    pub async fn in_memory() -> Result<Self, sqlx::Error> {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .min_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None)
+            .connect("sqlite::memory:")
+            .await?;
+        Self::init(pool).await
+    }
 
-        // Create tables if they don't exist
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS trainers (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                name TEXT NOT NULL,
-                created_date TEXT NOT NULL
-            )
-            "#,
-        )
-        .execute(&pool)
-        .await?;
+    /// Open (creating on first launch) a database file at the given path
+    pub async fn open(path: &Path) -> Result<Self, sqlx::Error> {
+        let options = SqliteConnectOptions::new()
+            .filename(path)
+            .create_if_missing(true);
+        let pool = SqlitePool::connect_with(options).await?;
+        Self::init(pool).await
+    }
 
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS user_pokemon (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                pokemon_id INTEGER NOT NULL,
-                nickname TEXT,
-                level INTEGER NOT NULL DEFAULT 1,
-                experience INTEGER NOT NULL DEFAULT 0,
-                captured_date TEXT NOT NULL,
-                is_shiny BOOLEAN NOT NULL DEFAULT FALSE,
-                trainer_id INTEGER NOT NULL,
-                FOREIGN KEY (trainer_id) REFERENCES trainers (id)
-            )
-            "#,
-        )
-        .execute(&pool)
-        .await?;
+    async fn init(pool: SqlitePool) -> Result<Self, sqlx::Error> {
+        migrations::run(&pool).await?;
 
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS pokemon_cache (
-                id INTEGER PRIMARY KEY,
-                name TEXT NOT NULL,
-                data TEXT NOT NULL,
-                last_updated TEXT NOT NULL
-            )
-            "#,
-        )
-        .execute(&pool)
-        .await?;
+        let database = Self { pool };
+        database.seed_conversion_profiles().await?;
+        database.fill_missing_challenge_ratings().await?;
 
-        Ok(Self { pool })
+        Ok(database)
     }
 
     // Trainer operations
@@ -107,6 +278,7 @@ impl Database {
     }
 
     // Pokemon collection operations
+    /// New captures join the party, or the first free box position once the party is full
     pub async fn capture_pokemon(
         &self,
         trainer_id: i64,
@@ -114,14 +286,19 @@ impl Database {
         nickname: Option<String>,
         level: u8,
         is_shiny: bool,
-    ) -> Result<i64, sqlx::Error> {
+    ) -> Result<i64, StorageError> {
         let captured_date = chrono::Utc::now().to_rfc3339();
 
+        let mut tx = self.pool.begin().await?;
+        let location = next_free_location(&mut tx, trainer_id).await?;
+        let (party_slot, box_id, box_position) = location.columns();
+
         let result = sqlx::query(
             r#"
             INSERT INTO user_pokemon 
-            (pokemon_id, nickname, level, experience, captured_date, is_shiny, trainer_id)
-            VALUES (?, ?, ?, ?, ?, ?, ?)
+            (pokemon_id, nickname, level, experience, captured_date, is_shiny, trainer_id,
+             party_slot, box_id, box_position)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(pokemon_id)
@@ -131,9 +308,14 @@ impl Database {
         .bind(captured_date)
         .bind(is_shiny)
         .bind(trainer_id)
-        .execute(&self.pool)
+        .bind(party_slot)
+        .bind(box_id)
+        .bind(box_position)
+        .execute(&mut *tx)
         .await?;
 
+        tx.commit().await?;
+
         Ok(result.last_insert_rowid())
     }
 
@@ -141,49 +323,410 @@ impl Database {
         &self,
         trainer_id: i64,
     ) -> Result<Vec<UserPokemon>, sqlx::Error> {
+        let sql = format!(
+            "SELECT {} FROM user_pokemon WHERE trainer_id = ? ORDER BY captured_date DESC",
+            USER_POKEMON_COLUMNS
+        );
+        let rows = sqlx::query(&sql)
+            .bind(trainer_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows.iter().map(user_pokemon_from_row).collect())
+    }
+
+    pub async fn get_user_pokemon(
+        &self,
+        user_pokemon_id: i64,
+    ) -> Result<Option<UserPokemon>, sqlx::Error> {
+        let sql = format!(
+            "SELECT {} FROM user_pokemon WHERE id = ?",
+            USER_POKEMON_COLUMNS
+        );
+        let row = sqlx::query(&sql)
+            .bind(user_pokemon_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.as_ref().map(user_pokemon_from_row))
+    }
+
+    pub async fn update_pokemon_customization(
+        &self,
+        user_pokemon_id: i64,
+        customization: &PokemonCustomization,
+    ) -> Result<(), sqlx::Error> {
+        let data = serde_json::to_string(customization).unwrap();
+
+        sqlx::query("UPDATE user_pokemon SET customization = ? WHERE id = ?")
+            .bind(data)
+            .bind(user_pokemon_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    // Party and box storage
+    pub async fn get_party(&self, trainer_id: i64) -> Result<Vec<UserPokemon>, sqlx::Error> {
+        let sql = format!(
+            "SELECT {} FROM user_pokemon WHERE trainer_id = ? AND party_slot IS NOT NULL \
+             ORDER BY party_slot",
+            USER_POKEMON_COLUMNS
+        );
+        let rows = sqlx::query(&sql)
+            .bind(trainer_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows.iter().map(user_pokemon_from_row).collect())
+    }
+
+    pub async fn get_boxes(&self, trainer_id: i64) -> Result<Vec<PokemonBox>, sqlx::Error> {
         let rows = sqlx::query(
-            r#"
-            SELECT id, pokemon_id, nickname, level, experience, captured_date, is_shiny, trainer_id
-            FROM user_pokemon WHERE trainer_id = ? ORDER BY captured_date DESC
-            "#,
+            "SELECT id, trainer_id, name, position FROM pokemon_boxes \
+             WHERE trainer_id = ? ORDER BY position, id",
         )
         .bind(trainer_id)
         .fetch_all(&self.pool)
         .await?;
 
-        let mut pokemon = Vec::new();
-        for row in rows {
-            pokemon.push(UserPokemon {
+        Ok(rows
+            .iter()
+            .map(|row| PokemonBox {
                 id: row.get("id"),
-                pokemon_id: row.get("pokemon_id"),
-                nickname: row.get("nickname"),
-                level: row.get("level"),
-                experience: row.get("experience"),
-                captured_date: row.get("captured_date"),
-                is_shiny: row.get("is_shiny"),
                 trainer_id: row.get("trainer_id"),
-            });
+                name: row.get("name"),
+                position: row.get("position"),
+            })
+            .collect())
+    }
+
+    pub async fn get_box_pokemon(&self, box_id: i64) -> Result<Vec<UserPokemon>, sqlx::Error> {
+        let sql = format!(
+            "SELECT {} FROM user_pokemon WHERE box_id = ? ORDER BY box_position",
+            USER_POKEMON_COLUMNS
+        );
+        let rows = sqlx::query(&sql).bind(box_id).fetch_all(&self.pool).await?;
+
+        Ok(rows.iter().map(user_pokemon_from_row).collect())
+    }
+
+    /// Add a box after the trainer's existing ones
+    pub async fn create_box(&self, trainer_id: i64, name: &str) -> Result<i64, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let box_id = insert_box(&mut tx, trainer_id, name).await?;
+        tx.commit().await?;
+        Ok(box_id)
+    }
+
+    pub async fn rename_box(&self, box_id: i64, name: &str) -> Result<(), StorageError> {
+        let result = sqlx::query("UPDATE pokemon_boxes SET name = ? WHERE id = ?")
+            .bind(name)
+            .bind(box_id)
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(StorageError::BoxNotFound(box_id));
         }
+        Ok(())
+    }
 
-        Ok(pokemon)
-    } // Pokemon data caching
+    /// Only empty boxes can be deleted, so no Pokemon is ever left without a location
+    pub async fn delete_box(&self, box_id: i64) -> Result<(), StorageError> {
+        let mut tx = self.pool.begin().await?;
+        box_trainer(&mut tx, box_id).await?;
+
+        let row = sqlx::query("SELECT COUNT(*) as count FROM user_pokemon WHERE box_id = ?")
+            .bind(box_id)
+            .fetch_one(&mut *tx)
+            .await?;
+        if row.get::<i64, _>("count") > 0 {
+            return Err(StorageError::BoxNotEmpty(box_id));
+        }
+
+        sqlx::query("DELETE FROM pokemon_boxes WHERE id = ?")
+            .bind(box_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Put a trainer's boxes in the given order; every one of their boxes must be listed
+    pub async fn reorder_boxes(
+        &self,
+        trainer_id: i64,
+        box_ids: &[i64],
+    ) -> Result<(), StorageError> {
+        let mut tx = self.pool.begin().await?;
+
+        let rows = sqlx::query("SELECT id FROM pokemon_boxes WHERE trainer_id = ?")
+            .bind(trainer_id)
+            .fetch_all(&mut *tx)
+            .await?;
+        let mut existing: Vec<i64> = rows.iter().map(|row| row.get("id")).collect();
+        let mut requested = box_ids.to_vec();
+        existing.sort_unstable();
+        requested.sort_unstable();
+        if existing != requested {
+            return Err(StorageError::InvalidLocation(
+                "Box order must list each of the trainer's boxes exactly once".to_string(),
+            ));
+        }
+
+        for (index, box_id) in box_ids.iter().enumerate() {
+            sqlx::query("UPDATE pokemon_boxes SET position = ? WHERE id = ?")
+                .bind(index as i64 + 1)
+                .bind(box_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Move an owned Pokemon to a party slot or box position. The party stays packed into
+    /// slots 1..n: moving into it inserts at the slot, and leaving it closes the gap.
+    pub async fn move_pokemon(
+        &self,
+        user_pokemon_id: i64,
+        to: StorageLocation,
+    ) -> Result<(), StorageError> {
+        let mut tx = self.pool.begin().await?;
+        move_within(&mut tx, user_pokemon_id, to).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Exchange the locations of two Pokemon owned by the same trainer
+    pub async fn swap_pokemon(&self, first_id: i64, second_id: i64) -> Result<(), StorageError> {
+        if first_id == second_id {
+            return Ok(());
+        }
+
+        let mut tx = self.pool.begin().await?;
+        let (first_trainer, first_location) = location_of(&mut tx, first_id).await?;
+        let (second_trainer, second_location) = location_of(&mut tx, second_id).await?;
+        if first_trainer != second_trainer {
+            return Err(StorageError::InvalidLocation(
+                "Only Pokemon belonging to the same trainer can be swapped".to_string(),
+            ));
+        }
+
+        // Vacate the first location before reusing it, to satisfy the unique slot indexes
+        set_location(&mut tx, first_id, None).await?;
+        set_location(&mut tx, second_id, Some(first_location)).await?;
+        set_location(&mut tx, first_id, Some(second_location)).await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Send a Pokemon to a box, at the given position or the first free one
+    pub async fn deposit_pokemon(
+        &self,
+        user_pokemon_id: i64,
+        box_id: i64,
+        position: Option<u32>,
+    ) -> Result<(), StorageError> {
+        let mut tx = self.pool.begin().await?;
+
+        let position = match position {
+            Some(position) => position,
+            None => first_free_box_position(&mut tx, box_id)
+                .await?
+                .ok_or(StorageError::BoxFull(box_id))?,
+        };
+        move_within(
+            &mut tx,
+            user_pokemon_id,
+            StorageLocation::Box { box_id, position },
+        )
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Bring a boxed Pokemon to the end of the party
+    pub async fn withdraw_pokemon(&self, user_pokemon_id: i64) -> Result<(), StorageError> {
+        let mut tx = self.pool.begin().await?;
+
+        let (trainer_id, location) = location_of(&mut tx, user_pokemon_id).await?;
+        if let StorageLocation::Party { .. } = location {
+            return Err(StorageError::InvalidLocation(format!(
+                "Pokemon {} is already in the party",
+                user_pokemon_id
+            )));
+        }
+        let party = party_members(&mut tx, trainer_id).await?;
+        move_within(
+            &mut tx,
+            user_pokemon_id,
+            StorageLocation::Party {
+                slot: party.len() as u8 + 1,
+            },
+        )
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    // Pokemon data caching
     pub async fn cache_pokemon(&self, pokemon: &Pokemon) -> Result<(), sqlx::Error> {
         let data = serde_json::to_string(pokemon).unwrap();
         let last_updated = chrono::Utc::now().to_rfc3339();
+        let stats = &pokemon.base_stats;
+        let challenge_rating = stored_challenge_rating(pokemon, &self.get_type_chart().await?);
+
+        let mut tx = self.pool.begin().await?;
 
         sqlx::query(
-            "INSERT OR REPLACE INTO pokemon_cache (id, name, data, last_updated) VALUES (?, ?, ?, ?)"
+            r#"
+            INSERT OR REPLACE INTO pokemon_cache
+            (id, name, data, last_updated, hp, attack, defense, special_attack, special_defense,
+             speed, base_stat_total, challenge_rating, generation)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?,
+                    COALESCE((SELECT generation FROM species_generations WHERE species_id = ?), ?))
+            "#,
         )
         .bind(pokemon.id as i64)
         .bind(&pokemon.name)
         .bind(data)
         .bind(last_updated)
-        .execute(&self.pool)
+        .bind(stats.hp)
+        .bind(stats.attack)
+        .bind(stats.defense)
+        .bind(stats.special_attack)
+        .bind(stats.special_defense)
+        .bind(stats.speed)
+        .bind(stats.total())
+        .bind(challenge_rating)
+        .bind(pokemon.id as i64)
+        .bind(generation_for_national_dex_id(pokemon.id))
+        .execute(&mut *tx)
         .await?;
 
+        sqlx::query("DELETE FROM pokemon_types WHERE pokemon_id = ?")
+            .bind(pokemon.id as i64)
+            .execute(&mut *tx)
+            .await?;
+
+        for pokemon_type in &pokemon.types {
+            sqlx::query("INSERT INTO pokemon_types (pokemon_id, type_name, slot) VALUES (?, ?, ?)")
+                .bind(pokemon.id as i64)
+                .bind(&pokemon_type.name)
+                .bind(pokemon_type.slot)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        sqlx::query("DELETE FROM pokemon_abilities WHERE pokemon_id = ?")
+            .bind(pokemon.id as i64)
+            .execute(&mut *tx)
+            .await?;
+
+        for ability in &pokemon.abilities {
+            sqlx::query(
+                "INSERT INTO pokemon_abilities (pokemon_id, ability_name, is_hidden, slot) VALUES (?, ?, ?, ?)",
+            )
+            .bind(pokemon.id as i64)
+            .bind(&ability.name)
+            .bind(ability.is_hidden)
+            .bind(ability.slot)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    // Species flavor text lives only in the search index; the Pokemon must already be cached
+    pub async fn set_pokemon_flavor_text(
+        &self,
+        pokemon_id: u32,
+        flavor_text: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE pokemon_search SET flavor_text = ? WHERE rowid = ?")
+            .bind(flavor_text)
+            .bind(pokemon_id as i64)
+            .execute(&self.pool)
+            .await?;
         Ok(())
     }
 
+    /// Ranked full-text search over cached names, types, abilities and flavor text.
+    /// Every word is prefix-matched; if nothing matches, falls back to the closest names
+    /// by edit distance so small typos still find something.
+    pub async fn search_pokemon(
+        &self,
+        text: &str,
+        limit: u32,
+    ) -> Result<Vec<Pokemon>, sqlx::Error> {
+        let match_query = match fts_match_query(text) {
+            Some(match_query) => match_query,
+            None => return Ok(Vec::new()),
+        };
+
+        // Column weights: name, types, abilities, flavor text
+        let rows = sqlx::query(
+            r#"
+            SELECT pokemon_cache.data FROM pokemon_search
+            JOIN pokemon_cache ON pokemon_cache.id = pokemon_search.rowid
+            WHERE pokemon_search MATCH ?
+            ORDER BY bm25(pokemon_search, 10.0, 4.0, 4.0, 1.0), pokemon_cache.id
+            LIMIT ?
+            "#,
+        )
+        .bind(match_query)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut pokemon: Vec<Pokemon> = rows
+            .iter()
+            .filter_map(|row| serde_json::from_str(&row.get::<String, _>("data")).ok())
+            .collect();
+        if !pokemon.is_empty() {
+            return Ok(pokemon);
+        }
+
+        let needle = text.trim().to_lowercase().replace([' ', '.', '\''], "-");
+        let length = needle.chars().count();
+        let max_distance = (length / 4).clamp(1, 3);
+
+        // Names further apart in length than the allowed distance can't match
+        let names =
+            sqlx::query("SELECT id, name FROM pokemon_cache WHERE length(name) BETWEEN ? AND ?")
+                .bind(length.saturating_sub(max_distance) as i64)
+                .bind((length + max_distance) as i64)
+                .fetch_all(&self.pool)
+                .await?;
+        let mut candidates: Vec<(usize, i64)> = names
+            .iter()
+            .filter_map(|row| {
+                let name: String = row.get("name");
+                let distance = edit_distance(&needle, &name);
+                (distance <= max_distance).then(|| (distance, row.get::<i64, _>("id")))
+            })
+            .collect();
+        candidates.sort();
+
+        for (_, id) in candidates.into_iter().take(limit as usize) {
+            if let Some(p) = self.get_cached_pokemon(id as u32).await? {
+                pokemon.push(p);
+            }
+        }
+
+        Ok(pokemon)
+    }
+
     pub async fn get_cached_pokemon(&self, id: u32) -> Result<Option<Pokemon>, sqlx::Error> {
         let row = sqlx::query("SELECT data FROM pokemon_cache WHERE id = ?")
             .bind(id as i64)
@@ -206,12 +749,16 @@ impl Database {
         id: u32,
         max_age_hours: i64,
     ) -> Result<bool, sqlx::Error> {
-        let row = sqlx::query("SELECT last_updated FROM pokemon_cache WHERE id = ?")
+        let row = sqlx::query("SELECT last_updated, imported FROM pokemon_cache WHERE id = ?")
             .bind(id as i64)
             .fetch_optional(&self.pool)
             .await?;
 
         if let Some(row) = row {
+            // Imported from a local dump: there is nothing fresher to fetch
+            if row.get::<bool, _>("imported") {
+                return Ok(true);
+            }
             let last_updated: String = row.get("last_updated");
             if let Ok(last_updated_time) = chrono::DateTime::parse_from_rfc3339(&last_updated) {
                 let now = chrono::Utc::now();
@@ -225,6 +772,16 @@ impl Database {
         }
     }
 
+    // Flag a cached Pokemon as coming from an api-data import. Caching it again from
+    // another source clears the flag.
+    pub async fn mark_pokemon_imported(&self, id: u32) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE pokemon_cache SET imported = TRUE WHERE id = ?")
+            .bind(id as i64)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
     pub async fn clear_pokemon_cache(&self) -> Result<(), sqlx::Error> {
         sqlx::query("DELETE FROM pokemon_cache")
             .execute(&self.pool)
@@ -239,10 +796,12 @@ impl Database {
         let cutoff_time = chrono::Utc::now() - chrono::Duration::hours(max_age_hours);
         let cutoff_string = cutoff_time.to_rfc3339();
 
-        let result = sqlx::query("DELETE FROM pokemon_cache WHERE last_updated < ?")
-            .bind(cutoff_string)
-            .execute(&self.pool)
-            .await?;
+        // Imported Pokemon never expire
+        let result =
+            sqlx::query("DELETE FROM pokemon_cache WHERE last_updated < ? AND imported = 0")
+                .bind(cutoff_string)
+                .execute(&self.pool)
+                .await?;
 
         Ok(result.rows_affected())
     }
@@ -254,13 +813,15 @@ impl Database {
         Ok(row.get("count"))
     }
 
-    // Check if we have a complete Pokemon dataset (Gen 1-8, approximately 1000 Pokemon)
-    pub async fn is_pokemon_database_complete(
-        &self,
-        expected_count: u32,
-    ) -> Result<bool, sqlx::Error> {
-        let count = self.get_cached_pokemon_count().await?;
-        Ok(count >= expected_count as i64)
+    pub async fn get_cached_pokemon_ids(&self) -> Result<Vec<u32>, sqlx::Error> {
+        let rows = sqlx::query("SELECT id FROM pokemon_cache ORDER BY id")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| row.get::<i64, _>("id") as u32)
+            .collect())
     }
 
     // Get Pokemon by ID range (much simpler than offset-based pagination)
@@ -304,44 +865,63 @@ impl Database {
         type_filter: Option<String>,
         search_query: Option<String>,
     ) -> Result<Vec<Pokemon>, sqlx::Error> {
-        let mut query = "SELECT data FROM pokemon_cache".to_string();
-        let mut conditions = Vec::new();
+        let query = PokemonQuery {
+            types: type_filter.into_iter().collect(),
+            search_query,
+            ..PokemonQuery::default()
+        };
+        self.query_pokemon(&query, Some(offset), Some(limit)).await
+    }
 
-        if type_filter.is_some() || search_query.is_some() {
-            conditions.push("1=1".to_string()); // Base condition for WHERE clause
-        }
+    pub async fn get_pokemon_count_filtered(
+        &self,
+        type_filter: Option<String>,
+        search_query: Option<String>,
+    ) -> Result<i64, sqlx::Error> {
+        let query = PokemonQuery {
+            types: type_filter.into_iter().collect(),
+            search_query,
+            ..PokemonQuery::default()
+        };
+        self.count_pokemon(&query).await
+    }
 
-        if let Some(ref query_text) = search_query {
-            conditions.push(format!("name LIKE '%{}%'", query_text.to_lowercase()));
+    /// Cached Pokemon matching a structured query, sorted and paged in SQL.
+    /// Pass no limit to fetch every match.
+    pub async fn query_pokemon(
+        &self,
+        query: &PokemonQuery,
+        offset: Option<u32>,
+        limit: Option<u32>,
+    ) -> Result<Vec<Pokemon>, sqlx::Error> {
+        let (where_clause, params) = pokemon_query_clause(query);
+        let direction = if query.descending { "DESC" } else { "ASC" };
+        let mut sql = format!(
+            "SELECT data FROM pokemon_cache{} ORDER BY {} {}, id ASC",
+            where_clause,
+            query.sort_by.column(),
+            direction
+        );
+        if limit.is_some() {
+            sql.push_str(" LIMIT ? OFFSET ?");
         }
 
-        if !conditions.is_empty() {
-            query.push_str(" WHERE ");
-            query.push_str(&conditions.join(" AND "));
+        let mut sql_query = sqlx::query(&sql);
+        for param in params {
+            sql_query = param.bind_to(sql_query);
+        }
+        if let Some(limit) = limit {
+            sql_query = sql_query
+                .bind(limit as i64)
+                .bind(offset.unwrap_or(0) as i64);
         }
 
-        query.push_str(" ORDER BY id LIMIT ? OFFSET ?");
-
-        let rows = sqlx::query(&query)
-            .bind(limit as i64)
-            .bind(offset as i64)
-            .fetch_all(&self.pool)
-            .await?;
+        let rows = sql_query.fetch_all(&self.pool).await?;
 
         let mut pokemon = Vec::new();
         for row in rows {
             let data: String = row.get("data");
             if let Ok(p) = serde_json::from_str::<Pokemon>(&data) {
-                // Apply type filter in memory since it's complex JSON parsing
-                if let Some(ref type_name) = type_filter {
-                    let has_type = p
-                        .types
-                        .iter()
-                        .any(|t| t.name.to_lowercase() == type_name.to_lowercase());
-                    if !has_type {
-                        continue;
-                    }
-                }
                 pokemon.push(p);
             }
         }
@@ -349,51 +929,496 @@ impl Database {
         Ok(pokemon)
     }
 
-    pub async fn get_pokemon_count_filtered(
-        &self,
-        type_filter: Option<String>,
-        search_query: Option<String>,
-    ) -> Result<i64, sqlx::Error> {
-        if type_filter.is_none() && search_query.is_none() {
-            return self.get_cached_pokemon_count().await;
-        }
+    pub async fn count_pokemon(&self, query: &PokemonQuery) -> Result<i64, sqlx::Error> {
+        let (where_clause, params) = pokemon_query_clause(query);
+        let sql = format!(
+            "SELECT COUNT(*) as count FROM pokemon_cache{}",
+            where_clause
+        );
 
-        let mut query = "SELECT COUNT(*) as count FROM pokemon_cache".to_string();
-        let mut conditions = Vec::new();
-
-        if let Some(ref query_text) = search_query {
-            conditions.push(format!("name LIKE '%{}%'", query_text.to_lowercase()));
+        let mut sql_query = sqlx::query(&sql);
+        for param in params {
+            sql_query = param.bind_to(sql_query);
         }
 
-        if !conditions.is_empty() {
-            query.push_str(" WHERE ");
-            query.push_str(&conditions.join(" AND "));
-        }
+        let row = sql_query.fetch_one(&self.pool).await?;
+        Ok(row.get("count"))
+    }
+
+    // Move data caching (moves are shared across species, so cache by name)
+    pub async fn cache_move(&self, pokemon_move: &Move) -> Result<(), sqlx::Error> {
+        let data = serde_json::to_string(pokemon_move).unwrap();
+        let last_updated = chrono::Utc::now().to_rfc3339();
+
+        sqlx::query(
+            "INSERT OR REPLACE INTO move_cache (name, data, last_updated) VALUES (?, ?, ?)",
+        )
+        .bind(&pokemon_move.name)
+        .bind(data)
+        .bind(last_updated)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_cached_move(&self, name: &str) -> Result<Option<Move>, sqlx::Error> {
+        let row = sqlx::query("SELECT data FROM move_cache WHERE name = ?")
+            .bind(name)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        if let Some(row) = row {
+            let data: String = row.get("data");
+            Ok(serde_json::from_str(&data).ok())
+        } else {
+            Ok(None)
+        }
+    }
+
+    // Species and evolution chain caching (these rarely change, so they never expire)
+    pub async fn cache_species(&self, species: &Species) -> Result<(), sqlx::Error> {
+        let data = serde_json::to_string(species).unwrap();
+        let last_updated = chrono::Utc::now().to_rfc3339();
+
+        sqlx::query(
+            "INSERT OR REPLACE INTO species_cache (id, name, data, last_updated) VALUES (?, ?, ?, ?)",
+        )
+        .bind(species.id as i64)
+        .bind(&species.name)
+        .bind(data)
+        .bind(last_updated)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_cached_species(&self, id: u32) -> Result<Option<Species>, sqlx::Error> {
+        let row = sqlx::query("SELECT data FROM species_cache WHERE id = ?")
+            .bind(id as i64)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        if let Some(row) = row {
+            let data: String = row.get("data");
+            Ok(serde_json::from_str(&data).ok())
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub async fn cache_evolution_chain(&self, chain: &EvolutionChain) -> Result<(), sqlx::Error> {
+        let data = serde_json::to_string(chain).unwrap();
+        let last_updated = chrono::Utc::now().to_rfc3339();
+
+        sqlx::query(
+            "INSERT OR REPLACE INTO evolution_chain_cache (id, data, last_updated) VALUES (?, ?, ?)",
+        )
+        .bind(chain.id as i64)
+        .bind(data)
+        .bind(last_updated)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_cached_evolution_chain(
+        &self,
+        id: u32,
+    ) -> Result<Option<EvolutionChain>, sqlx::Error> {
+        let row = sqlx::query("SELECT data FROM evolution_chain_cache WHERE id = ?")
+            .bind(id as i64)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        if let Some(row) = row {
+            let data: String = row.get("data");
+            Ok(serde_json::from_str(&data).ok())
+        } else {
+            Ok(None)
+        }
+    }
+
+    // Generation metadata. Saving a generation also moves its cached Pokemon into it,
+    // so filters see the ingested generation rather than the National Dex estimate.
+    pub async fn save_generation(&self, generation: &Generation) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("INSERT OR REPLACE INTO generations (id, name, main_region) VALUES (?, ?, ?)")
+            .bind(generation.id as i64)
+            .bind(&generation.name)
+            .bind(&generation.main_region)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("DELETE FROM species_generations WHERE generation = ?")
+            .bind(generation.id as i64)
+            .execute(&mut *tx)
+            .await?;
+
+        for species_id in &generation.species_ids {
+            sqlx::query(
+                "INSERT OR REPLACE INTO species_generations (species_id, generation) VALUES (?, ?)",
+            )
+            .bind(*species_id as i64)
+            .bind(generation.id as i64)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        sqlx::query(
+            "UPDATE pokemon_cache SET generation = ? \
+             WHERE id IN (SELECT species_id FROM species_generations WHERE generation = ?)",
+        )
+        .bind(generation.id as i64)
+        .bind(generation.id as i64)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await
+    }
+
+    /// Replace the stored type chart
+    pub async fn save_type_chart(&self, chart: &TypeChart) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM type_efficacy")
+            .execute(&mut *tx)
+            .await?;
+        for (attacking, defending, multiplier) in chart.matchups() {
+            sqlx::query(
+                "INSERT INTO type_efficacy (attacking_type, defending_type, multiplier) VALUES (?, ?, ?)",
+            )
+            .bind(attacking)
+            .bind(defending)
+            .bind(multiplier)
+            .execute(&mut *tx)
+            .await?;
+        }
+        // Resistances feed into challenge rating, so every stored one is now stale
+        sqlx::query("UPDATE pokemon_cache SET challenge_rating = NULL")
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        self.fill_missing_challenge_ratings().await
+    }
+
+    // Work out challenge ratings for rows cached before they were stored or written directly
+    async fn fill_missing_challenge_ratings(&self) -> Result<(), sqlx::Error> {
+        let rows = sqlx::query("SELECT id, data FROM pokemon_cache WHERE challenge_rating IS NULL")
+            .fetch_all(&self.pool)
+            .await?;
+        if rows.is_empty() {
+            return Ok(());
+        }
+
+        let type_chart = self.get_type_chart().await?;
+        let mut tx = self.pool.begin().await?;
+        for row in rows {
+            let pokemon: Pokemon = match serde_json::from_str(&row.get::<String, _>("data")) {
+                Ok(pokemon) => pokemon,
+                Err(_) => continue,
+            };
+            sqlx::query("UPDATE pokemon_cache SET challenge_rating = ? WHERE id = ?")
+                .bind(stored_challenge_rating(&pokemon, &type_chart))
+                .bind(row.get::<i64, _>("id"))
+                .execute(&mut *tx)
+                .await?;
+        }
 
-        let row = sqlx::query(&query).fetch_one(&self.pool).await?;
+        tx.commit().await
+    }
+
+    /// The imported type chart, or the built-in one if none has been imported
+    pub async fn get_type_chart(&self) -> Result<TypeChart, sqlx::Error> {
+        let rows =
+            sqlx::query("SELECT attacking_type, defending_type, multiplier FROM type_efficacy")
+                .fetch_all(&self.pool)
+                .await?;
+
+        if rows.is_empty() {
+            return Ok(TypeChart::default());
+        }
+        Ok(TypeChart::from_matchups(rows.iter().map(|row| {
+            (
+                row.get("attacking_type"),
+                row.get("defending_type"),
+                row.get::<f64, _>("multiplier") as f32,
+            )
+        })))
+    }
 
-        let mut count: i64 = row.get("count");
+    pub async fn get_generations(&self) -> Result<Vec<Generation>, sqlx::Error> {
+        let rows = sqlx::query("SELECT id, name, main_region FROM generations ORDER BY id")
+            .fetch_all(&self.pool)
+            .await?;
 
-        // If type filter is specified, we need to check in memory
-        if type_filter.is_some() {
-            // This is a simplified approach - in a real app, you'd want to
-            // store type information in a separate column for efficient querying
-            let all_pokemon = self
-                .get_pokemon_batch_filtered(0, count as u32, type_filter, search_query.clone())
+        let mut generations = Vec::new();
+        for row in rows {
+            let id = row.get::<i64, _>("id") as u32;
+            generations.push(Generation {
+                id,
+                name: row.get("name"),
+                main_region: row.get("main_region"),
+                species_ids: self.get_generation_species_ids(id).await?,
+            });
+        }
+
+        Ok(generations)
+    }
+
+    pub async fn get_generation_species_ids(
+        &self,
+        generation: u32,
+    ) -> Result<Vec<u32>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT species_id FROM species_generations WHERE generation = ? ORDER BY species_id",
+        )
+        .bind(generation as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| row.get::<i64, _>("species_id") as u32)
+            .collect())
+    }
+
+    // Pokedexes, replacing any earlier entries for the same dex
+    pub async fn save_pokedex(
+        &self,
+        pokedex: &Pokedex,
+        entries: &[DexEntry],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            "INSERT OR REPLACE INTO pokedexes (name, id, region, is_main_series) VALUES (?, ?, ?, ?)",
+        )
+        .bind(&pokedex.name)
+        .bind(pokedex.id as i64)
+        .bind(&pokedex.region)
+        .bind(pokedex.is_main_series)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("DELETE FROM pokedex_entries WHERE pokedex = ?")
+            .bind(&pokedex.name)
+            .execute(&mut *tx)
+            .await?;
+
+        for entry in entries {
+            sqlx::query(
+                "INSERT OR REPLACE INTO pokedex_entries (pokedex, entry_number, species_id) VALUES (?, ?, ?)",
+            )
+            .bind(&pokedex.name)
+            .bind(entry.entry_number)
+            .bind(entry.species_id as i64)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await
+    }
+
+    pub async fn get_pokedexes(&self) -> Result<Vec<Pokedex>, sqlx::Error> {
+        let rows =
+            sqlx::query("SELECT name, id, region, is_main_series FROM pokedexes ORDER BY id")
+                .fetch_all(&self.pool)
                 .await?;
-            count = all_pokemon.len() as i64;
+
+        Ok(rows
+            .iter()
+            .map(|row| Pokedex {
+                id: row.get::<i64, _>("id") as u32,
+                name: row.get("name"),
+                region: row.get("region"),
+                is_main_series: row.get("is_main_series"),
+            })
+            .collect())
+    }
+
+    // Species in a Pokedex, in dex order
+    pub async fn get_pokedex_species_ids(&self, pokedex: &str) -> Result<Vec<u32>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT species_id FROM pokedex_entries WHERE pokedex = ? ORDER BY entry_number",
+        )
+        .bind(pokedex)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| row.get::<i64, _>("species_id") as u32)
+            .collect())
+    }
+
+    // Every dex number a species has, National Dex first
+    pub async fn get_dex_entries(&self, species_id: u32) -> Result<Vec<DexEntry>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT e.pokedex, e.entry_number, e.species_id
+            FROM pokedex_entries e JOIN pokedexes p ON p.name = e.pokedex
+            WHERE e.species_id = ?
+            ORDER BY p.id
+            "#,
+        )
+        .bind(species_id as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| DexEntry {
+                pokedex: row.get("pokedex"),
+                entry_number: row.get("entry_number"),
+                species_id: row.get::<i64, _>("species_id") as u32,
+            })
+            .collect())
+    }
+
+    // Conversion profiles (presets are seeded once and can then be tuned like any other)
+    async fn seed_conversion_profiles(&self) -> Result<(), sqlx::Error> {
+        for profile in ConversionProfile::presets() {
+            let data = serde_json::to_string(&profile).unwrap();
+            let last_updated = chrono::Utc::now().to_rfc3339();
+
+            sqlx::query(
+                "INSERT OR IGNORE INTO conversion_profiles (name, data, last_updated) VALUES (?, ?, ?)",
+            )
+            .bind(&profile.name)
+            .bind(data)
+            .bind(last_updated)
+            .execute(&self.pool)
+            .await?;
         }
 
-        Ok(count)
+        Ok(())
+    }
+
+    pub async fn save_conversion_profile(
+        &self,
+        profile: &ConversionProfile,
+    ) -> Result<(), sqlx::Error> {
+        let data = serde_json::to_string(profile).unwrap();
+        let last_updated = chrono::Utc::now().to_rfc3339();
+
+        sqlx::query(
+            "INSERT OR REPLACE INTO conversion_profiles (name, data, last_updated) VALUES (?, ?, ?)",
+        )
+        .bind(&profile.name)
+        .bind(data)
+        .bind(last_updated)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
     }
 
-    pub async fn update_pokemon_level(
+    pub async fn get_conversion_profile(
+        &self,
+        name: &str,
+    ) -> Result<Option<ConversionProfile>, sqlx::Error> {
+        let row = sqlx::query("SELECT data FROM conversion_profiles WHERE name = ?")
+            .bind(name)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        if let Some(row) = row {
+            let data: String = row.get("data");
+            Ok(serde_json::from_str(&data).ok())
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub async fn get_conversion_profiles(&self) -> Result<Vec<ConversionProfile>, sqlx::Error> {
+        let rows = sqlx::query("SELECT data FROM conversion_profiles ORDER BY name")
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut profiles = Vec::new();
+        for row in rows {
+            let data: String = row.get("data");
+            if let Ok(profile) = serde_json::from_str::<ConversionProfile>(&data) {
+                profiles.push(profile);
+            }
+        }
+
+        Ok(profiles)
+    }
+
+    pub async fn delete_conversion_profile(&self, name: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM conversion_profiles WHERE name = ?")
+            .bind(name)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    // Bulk load bookkeeping: loaded Pokemon leave the failed list, failures join it
+    // with their attempts added to any from earlier runs
+    pub async fn record_load_report(&self, report: &LoadReport) -> Result<(), sqlx::Error> {
+        let last_attempt = chrono::Utc::now().to_rfc3339();
+        let mut tx = self.pool.begin().await?;
+
+        for id in &report.loaded {
+            sqlx::query("DELETE FROM failed_pokemon_loads WHERE pokemon_id = ?")
+                .bind(*id as i64)
+                .execute(&mut *tx)
+                .await?;
+        }
+        for failure in &report.failed {
+            sqlx::query(
+                r#"
+                INSERT INTO failed_pokemon_loads (pokemon_id, error, attempts, last_attempt)
+                VALUES (?, ?, ?, ?)
+                ON CONFLICT (pokemon_id) DO UPDATE SET
+                    error = excluded.error,
+                    attempts = attempts + excluded.attempts,
+                    last_attempt = excluded.last_attempt
+                "#,
+            )
+            .bind(failure.pokemon_id as i64)
+            .bind(&failure.error)
+            .bind(failure.attempts)
+            .bind(&last_attempt)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await
+    }
+
+    pub async fn get_failed_loads(&self) -> Result<Vec<FailedLoad>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT pokemon_id, error, attempts FROM failed_pokemon_loads ORDER BY pokemon_id",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| FailedLoad {
+                pokemon_id: row.get::<i64, _>("pokemon_id") as u32,
+                error: row.get("error"),
+                attempts: row.get("attempts"),
+            })
+            .collect())
+    }
+
+    pub async fn set_pokemon_progress(
         &self,
         user_pokemon_id: i64,
-        new_level: u8,
+        level: u8,
+        experience: u32,
     ) -> Result<(), sqlx::Error> {
-        sqlx::query("UPDATE user_pokemon SET level = ? WHERE id = ?")
-            .bind(new_level)
+        sqlx::query("UPDATE user_pokemon SET level = ?, experience = ? WHERE id = ?")
+            .bind(level)
+            .bind(experience)
             .bind(user_pokemon_id)
             .execute(&self.pool)
             .await?;
@@ -401,11 +1426,811 @@ impl Database {
         Ok(())
     }
 
-    pub async fn release_pokemon(&self, user_pokemon_id: i64) -> Result<(), sqlx::Error> {
-        sqlx::query("DELETE FROM user_pokemon WHERE id = ?")
+    // Evolution swaps the species only; nickname, level, experience and capture date stay
+    pub async fn evolve_pokemon(
+        &self,
+        user_pokemon_id: i64,
+        pokemon_id: u32,
+        customization: &PokemonCustomization,
+    ) -> Result<(), sqlx::Error> {
+        let data = serde_json::to_string(customization).unwrap();
+
+        sqlx::query("UPDATE user_pokemon SET pokemon_id = ?, customization = ? WHERE id = ?")
+            .bind(pokemon_id)
+            .bind(data)
             .bind(user_pokemon_id)
             .execute(&self.pool)
             .await?;
+
         Ok(())
     }
+
+    pub async fn release_pokemon(&self, user_pokemon_id: i64) -> Result<(), StorageError> {
+        let mut tx = self.pool.begin().await?;
+        let (trainer_id, location) = location_of(&mut tx, user_pokemon_id).await?;
+
+        sqlx::query("DELETE FROM user_pokemon WHERE id = ?")
+            .bind(user_pokemon_id)
+            .execute(&mut *tx)
+            .await?;
+
+        // Close the gap left in the party
+        if let StorageLocation::Party { .. } = location {
+            let party = party_members(&mut tx, trainer_id).await?;
+            write_party(&mut tx, trainer_id, &party).await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+}
+
+const USER_POKEMON_COLUMNS: &str = "id, pokemon_id, nickname, level, experience, captured_date, \
+     is_shiny, trainer_id, party_slot, box_id, box_position, customization";
+
+fn user_pokemon_from_row(row: &SqliteRow) -> UserPokemon {
+    UserPokemon {
+        id: row.get("id"),
+        pokemon_id: row.get("pokemon_id"),
+        nickname: row.get("nickname"),
+        level: row.get("level"),
+        experience: row.get("experience"),
+        captured_date: row.get("captured_date"),
+        is_shiny: row.get("is_shiny"),
+        trainer_id: row.get("trainer_id"),
+        location: storage_location_from_row(row),
+        customization: row
+            .get::<Option<String>, _>("customization")
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or_default(),
+    }
+}
+
+fn storage_location_from_row(row: &SqliteRow) -> Option<StorageLocation> {
+    let party_slot: Option<u8> = row.get("party_slot");
+    let box_id: Option<i64> = row.get("box_id");
+    let box_position: Option<u32> = row.get("box_position");
+
+    match (party_slot, box_id, box_position) {
+        (Some(slot), _, _) => Some(StorageLocation::Party { slot }),
+        (None, Some(box_id), Some(position)) => Some(StorageLocation::Box { box_id, position }),
+        _ => None,
+    }
+}
+
+// Storage helpers below run inside the caller's transaction
+
+async fn location_of(
+    conn: &mut SqliteConnection,
+    user_pokemon_id: i64,
+) -> Result<(i64, StorageLocation), StorageError> {
+    let row = sqlx::query(
+        "SELECT trainer_id, party_slot, box_id, box_position FROM user_pokemon WHERE id = ?",
+    )
+    .bind(user_pokemon_id)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or(StorageError::PokemonNotFound(user_pokemon_id))?;
+
+    let location = storage_location_from_row(&row).ok_or_else(|| {
+        StorageError::InvalidLocation(format!(
+            "Pokemon {} has no storage location",
+            user_pokemon_id
+        ))
+    })?;
+    Ok((row.get("trainer_id"), location))
+}
+
+async fn set_location(
+    conn: &mut SqliteConnection,
+    user_pokemon_id: i64,
+    location: Option<StorageLocation>,
+) -> Result<(), sqlx::Error> {
+    let (party_slot, box_id, box_position) = location
+        .map(|location| location.columns())
+        .unwrap_or((None, None, None));
+
+    sqlx::query(
+        "UPDATE user_pokemon SET party_slot = ?, box_id = ?, box_position = ? WHERE id = ?",
+    )
+    .bind(party_slot)
+    .bind(box_id)
+    .bind(box_position)
+    .bind(user_pokemon_id)
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+// Ids of the trainer's party, in slot order
+async fn party_members(
+    conn: &mut SqliteConnection,
+    trainer_id: i64,
+) -> Result<Vec<i64>, sqlx::Error> {
+    let rows = sqlx::query(
+        "SELECT id FROM user_pokemon WHERE trainer_id = ? AND party_slot IS NOT NULL ORDER BY party_slot",
+    )
+    .bind(trainer_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    Ok(rows.iter().map(|row| row.get("id")).collect())
+}
+
+// Renumber the party to exactly these members in slots 1..n
+async fn write_party(
+    conn: &mut SqliteConnection,
+    trainer_id: i64,
+    members: &[i64],
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "UPDATE user_pokemon SET party_slot = NULL WHERE trainer_id = ? AND party_slot IS NOT NULL",
+    )
+    .bind(trainer_id)
+    .execute(&mut *conn)
+    .await?;
+
+    for (index, member) in members.iter().enumerate() {
+        let slot = StorageLocation::Party {
+            slot: index as u8 + 1,
+        };
+        set_location(conn, *member, Some(slot)).await?;
+    }
+    Ok(())
+}
+
+async fn box_trainer(conn: &mut SqliteConnection, box_id: i64) -> Result<i64, StorageError> {
+    let row = sqlx::query("SELECT trainer_id FROM pokemon_boxes WHERE id = ?")
+        .bind(box_id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or(StorageError::BoxNotFound(box_id))?;
+    Ok(row.get("trainer_id"))
+}
+
+async fn box_occupant(
+    conn: &mut SqliteConnection,
+    box_id: i64,
+    position: u32,
+) -> Result<Option<i64>, sqlx::Error> {
+    let row = sqlx::query("SELECT id FROM user_pokemon WHERE box_id = ? AND box_position = ?")
+        .bind(box_id)
+        .bind(position)
+        .fetch_optional(&mut *conn)
+        .await?;
+    Ok(row.map(|row| row.get("id")))
+}
+
+async fn first_free_box_position(
+    conn: &mut SqliteConnection,
+    box_id: i64,
+) -> Result<Option<u32>, StorageError> {
+    box_trainer(conn, box_id).await?;
+
+    let rows = sqlx::query("SELECT box_position FROM user_pokemon WHERE box_id = ?")
+        .bind(box_id)
+        .fetch_all(&mut *conn)
+        .await?;
+    let taken: Vec<u32> = rows.iter().map(|row| row.get("box_position")).collect();
+
+    Ok((1..=BOX_CAPACITY).find(|position| !taken.contains(position)))
+}
+
+async fn insert_box(
+    conn: &mut SqliteConnection,
+    trainer_id: i64,
+    name: &str,
+) -> Result<i64, sqlx::Error> {
+    let row = sqlx::query(
+        "SELECT COALESCE(MAX(position), 0) as last_position FROM pokemon_boxes WHERE trainer_id = ?",
+    )
+    .bind(trainer_id)
+    .fetch_one(&mut *conn)
+    .await?;
+    let position = row.get::<i64, _>("last_position") + 1;
+
+    let result =
+        sqlx::query("INSERT INTO pokemon_boxes (trainer_id, name, position) VALUES (?, ?, ?)")
+            .bind(trainer_id)
+            .bind(name)
+            .bind(position)
+            .execute(&mut *conn)
+            .await?;
+    Ok(result.last_insert_rowid())
+}
+
+// Where a new capture goes: the next party slot, else the first free box position,
+// else a fresh box
+async fn next_free_location(
+    conn: &mut SqliteConnection,
+    trainer_id: i64,
+) -> Result<StorageLocation, StorageError> {
+    let party = party_members(conn, trainer_id).await?;
+    if party.len() < PARTY_SIZE as usize {
+        return Ok(StorageLocation::Party {
+            slot: party.len() as u8 + 1,
+        });
+    }
+
+    let rows =
+        sqlx::query("SELECT id FROM pokemon_boxes WHERE trainer_id = ? ORDER BY position, id")
+            .bind(trainer_id)
+            .fetch_all(&mut *conn)
+            .await?;
+    let box_ids: Vec<i64> = rows.iter().map(|row| row.get("id")).collect();
+
+    for box_id in &box_ids {
+        if let Some(position) = first_free_box_position(conn, *box_id).await? {
+            return Ok(StorageLocation::Box {
+                box_id: *box_id,
+                position,
+            });
+        }
+    }
+
+    let name = format!("Box {}", box_ids.len() + 1);
+    let box_id = insert_box(conn, trainer_id, &name).await?;
+    Ok(StorageLocation::Box {
+        box_id,
+        position: 1,
+    })
+}
+
+async fn move_within(
+    conn: &mut SqliteConnection,
+    user_pokemon_id: i64,
+    to: StorageLocation,
+) -> Result<(), StorageError> {
+    let (trainer_id, from) = location_of(conn, user_pokemon_id).await?;
+    let mut party = party_members(conn, trainer_id).await?;
+    let in_party = matches!(from, StorageLocation::Party { .. });
+
+    match to {
+        StorageLocation::Party { slot } => {
+            party.retain(|member| *member != user_pokemon_id);
+            if !in_party && party.len() >= PARTY_SIZE as usize {
+                return Err(StorageError::PartyFull);
+            }
+            if slot == 0 || slot as usize > party.len() + 1 {
+                return Err(StorageError::InvalidLocation(format!(
+                    "Party slot {} is out of range",
+                    slot
+                )));
+            }
+
+            party.insert(slot as usize - 1, user_pokemon_id);
+            write_party(conn, trainer_id, &party).await?;
+        }
+        StorageLocation::Box { box_id, position } => {
+            if box_trainer(conn, box_id).await? != trainer_id {
+                return Err(StorageError::InvalidLocation(format!(
+                    "Box {} belongs to another trainer",
+                    box_id
+                )));
+            }
+            if position == 0 || position > BOX_CAPACITY {
+                return Err(StorageError::InvalidLocation(format!(
+                    "Box position {} is out of range",
+                    position
+                )));
+            }
+            if let Some(occupant) = box_occupant(conn, box_id, position).await? {
+                if occupant != user_pokemon_id {
+                    return Err(StorageError::PositionTaken { box_id, position });
+                }
+            }
+
+            if in_party {
+                if party.len() == 1 {
+                    return Err(StorageError::LastPartyMember);
+                }
+                party.retain(|member| *member != user_pokemon_id);
+                write_party(conn, trainer_id, &party).await?;
+            }
+            set_location(conn, user_pokemon_id, Some(to)).await?;
+        }
+    }
+
+    Ok(())
+}
+
+enum SqlParam {
+    Text(String),
+    Integer(i64),
+    Real(f64),
+}
+
+impl SqlParam {
+    fn bind_to<'q>(
+        self,
+        query: sqlx::query::Query<'q, sqlx::Sqlite, sqlx::sqlite::SqliteArguments<'q>>,
+    ) -> sqlx::query::Query<'q, sqlx::Sqlite, sqlx::sqlite::SqliteArguments<'q>> {
+        match self {
+            SqlParam::Text(value) => query.bind(value),
+            SqlParam::Integer(value) => query.bind(value),
+            SqlParam::Real(value) => query.bind(value),
+        }
+    }
+}
+
+// Challenge rating stored with each cached Pokemon: the default profile at CHALLENGE_RATING_LEVEL
+fn stored_challenge_rating(pokemon: &Pokemon, type_chart: &TypeChart) -> f32 {
+    DnDStatBlock::from_pokemon(
+        pokemon,
+        CHALLENGE_RATING_LEVEL,
+        &ConversionProfile::default(),
+        type_chart,
+    )
+    .challenge_rating
+}
+
+// WHERE clause (with its bind values, in order) shared by the query and its count.
+// Only whitelisted column names are ever formatted into the SQL; all values are bound.
+fn pokemon_query_clause(query: &PokemonQuery) -> (String, Vec<SqlParam>) {
+    let mut conditions: Vec<String> = Vec::new();
+    let mut params = Vec::new();
+
+    if !query.types.is_empty() {
+        let placeholders = vec!["?"; query.types.len()].join(", ");
+        let subquery = format!(
+            "SELECT COUNT(DISTINCT t.type_name) FROM pokemon_types t \
+             WHERE t.pokemon_id = pokemon_cache.id AND t.type_name IN ({})",
+            placeholders
+        );
+        let required = if query.match_all_types {
+            query.types.len()
+        } else {
+            1
+        };
+        conditions.push(format!("({}) >= {}", subquery, required));
+        params.extend(query.types.iter().map(|t| SqlParam::Text(t.to_lowercase())));
+    }
+
+    if !query.generations.is_empty() {
+        let placeholders = vec!["?"; query.generations.len()].join(", ");
+        conditions.push(format!("generation IN ({})", placeholders));
+        params.extend(
+            query
+                .generations
+                .iter()
+                .map(|g| SqlParam::Integer(*g as i64)),
+        );
+    }
+
+    if let Some(pokedex) = &query.pokedex {
+        conditions
+            .push("id IN (SELECT species_id FROM pokedex_entries WHERE pokedex = ?)".to_string());
+        params.push(SqlParam::Text(pokedex.to_lowercase()));
+    }
+
+    for range in &query.stat_ranges {
+        if let Some(min) = range.min {
+            conditions.push(format!("{} >= ?", range.stat.column()));
+            params.push(SqlParam::Integer(min as i64));
+        }
+        if let Some(max) = range.max {
+            conditions.push(format!("{} <= ?", range.stat.column()));
+            params.push(SqlParam::Integer(max as i64));
+        }
+    }
+
+    if let Some(min) = query.min_base_stat_total {
+        conditions.push("base_stat_total >= ?".to_string());
+        params.push(SqlParam::Integer(min as i64));
+    }
+    if let Some(max) = query.max_base_stat_total {
+        conditions.push("base_stat_total <= ?".to_string());
+        params.push(SqlParam::Integer(max as i64));
+    }
+
+    if let Some(min) = query.min_challenge_rating {
+        conditions.push("challenge_rating >= ?".to_string());
+        params.push(SqlParam::Real(min as f64));
+    }
+    if let Some(max) = query.max_challenge_rating {
+        conditions.push("challenge_rating <= ?".to_string());
+        params.push(SqlParam::Real(max as f64));
+    }
+
+    if let Some(ability) = &query.ability {
+        conditions.push(
+            "EXISTS (SELECT 1 FROM pokemon_abilities a \
+             WHERE a.pokemon_id = pokemon_cache.id AND a.ability_name = ?)"
+                .to_string(),
+        );
+        params.push(SqlParam::Text(ability.to_lowercase()));
+    }
+
+    if let Some(match_query) = query.search_query.as_deref().and_then(fts_match_query) {
+        conditions.push(
+            "id IN (SELECT rowid FROM pokemon_search WHERE pokemon_search MATCH ?)".to_string(),
+        );
+        params.push(SqlParam::Text(match_query));
+    }
+
+    if conditions.is_empty() {
+        (String::new(), params)
+    } else {
+        (format!(" WHERE {}", conditions.join(" AND ")), params)
+    }
+}
+
+// FTS5 MATCH expression requiring every word of the input as a prefix.
+// Words are quoted so user input can never be read as FTS query syntax.
+fn fts_match_query(text: &str) -> Option<String> {
+    let terms: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| format!("\"{}\"*", word.to_lowercase()))
+        .collect();
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+// Levenshtein distance between two strings, by character
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{bulbasaur, snorlax, TempDatabase};
+
+    #[tokio::test]
+    async fn search_matches_prefixes_abilities_and_typos() {
+        let db = TempDatabase::new().await;
+        let mut pikachu = Pokemon::new(25, "pikachu".to_string());
+        pikachu.abilities = vec![crate::pokemon::Ability {
+            name: "static".to_string(),
+            is_hidden: false,
+            slot: 1,
+        }];
+        db.cache_pokemon(&pikachu).await.unwrap();
+        db.cache_pokemon(&snorlax()).await.unwrap();
+
+        let names =
+            |found: Vec<Pokemon>| -> Vec<String> { found.into_iter().map(|p| p.name).collect() };
+        assert_eq!(
+            names(db.search_pokemon("snor", 10).await.unwrap()),
+            vec!["snorlax"]
+        );
+        assert_eq!(
+            names(db.search_pokemon("static", 10).await.unwrap()),
+            vec!["pikachu"]
+        );
+        assert_eq!(
+            names(db.search_pokemon("pikachoo", 10).await.unwrap()),
+            vec!["pikachu"]
+        );
+        assert!(db.search_pokemon("mewtwo", 10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn search_index_follows_direct_cache_writes() {
+        let db = TempDatabase::new().await;
+        let mut pokemon = snorlax();
+        sqlx::query(
+            "INSERT INTO pokemon_cache (id, name, data, last_updated) VALUES (?, ?, ?, '')",
+        )
+        .bind(pokemon.id as i64)
+        .bind(&pokemon.name)
+        .bind(serde_json::to_string(&pokemon).unwrap())
+        .execute(&db.pool)
+        .await
+        .unwrap();
+        db.set_pokemon_flavor_text(pokemon.id, "It is not satisfied unless it eats")
+            .await
+            .unwrap();
+
+        let found = |text: &'static str| {
+            let db = &db;
+            async move { db.search_pokemon(text, 10).await.unwrap().len() }
+        };
+        assert_eq!(found("normal").await, 1);
+        assert_eq!(found("satisfied").await, 1);
+
+        pokemon.types[0].name = "ghost".to_string();
+        sqlx::query("UPDATE pokemon_cache SET data = ? WHERE id = ?")
+            .bind(serde_json::to_string(&pokemon).unwrap())
+            .bind(pokemon.id as i64)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+        assert_eq!(found("ghost").await, 1);
+        assert_eq!(found("normal").await, 0);
+        assert_eq!(found("satisfied").await, 1);
+
+        db.cache_pokemon(&snorlax()).await.unwrap();
+        assert_eq!(found("normal").await, 1);
+        assert_eq!(found("satisfied").await, 1);
+    }
+
+    #[tokio::test]
+    async fn challenge_ratings_are_stored_and_filtered_in_sql() {
+        let db = TempDatabase::new().await;
+        db.cache_pokemon(&snorlax()).await.unwrap();
+        db.cache_pokemon(&bulbasaur()).await.unwrap();
+        let snorlax_cr = stored_challenge_rating(&snorlax(), &TypeChart::default());
+        assert_ne!(
+            snorlax_cr,
+            stored_challenge_rating(&bulbasaur(), &TypeChart::default())
+        );
+
+        let query = PokemonQuery {
+            min_challenge_rating: Some(snorlax_cr),
+            max_challenge_rating: Some(snorlax_cr),
+            ..PokemonQuery::default()
+        };
+        let found = db.query_pokemon(&query, None, None).await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].name, "snorlax");
+
+        // Ratings missing from the cache are worked out when it is next opened
+        sqlx::query("UPDATE pokemon_cache SET challenge_rating = NULL")
+            .execute(&db.pool)
+            .await
+            .unwrap();
+        assert_eq!(db.count_pokemon(&query).await.unwrap(), 0);
+        let reopened = Database::open(db.path()).await.unwrap();
+        assert_eq!(reopened.count_pokemon(&query).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn profiles_saved_in_snake_case_are_migrated() {
+        let db = TempDatabase::new().await;
+        let saved = serde_json::json!({
+            "name": "old",
+            "level_multiplier": 0.02,
+            "ability_score_scale": 0.07,
+            "stat_scaling": {
+                "hp_multiplier": 0.75,
+                "attack_multiplier": 0.9,
+                "defense_multiplier": 1.0,
+                "speed_multiplier": 0.9,
+                "min_ability_score": 6,
+                "max_ability_score": 18,
+            },
+            "damage_types": crate::converter::DamageTypeMap::default(),
+        });
+        sqlx::query(
+            "INSERT INTO conversion_profiles (name, data, last_updated) VALUES ('old', ?, '')",
+        )
+        .bind(saved.to_string())
+        .execute(&db.pool)
+        .await
+        .unwrap();
+        let camel_case = migrations::MIGRATIONS
+            .iter()
+            .find(|m| m.description == "camelCase conversion profiles")
+            .unwrap();
+        for statement in camel_case.statements {
+            sqlx::query(statement).execute(&db.pool).await.unwrap();
+        }
+
+        let profile = db.get_conversion_profile("old").await.unwrap().unwrap();
+        assert_eq!(profile.ability_score_scale, 0.07);
+        assert_eq!(profile.stat_scaling.max_ability_score, 18);
+        assert_eq!(profile.damage_types.mappings.len(), 18);
+        assert_eq!(profile.validate(), Ok(()));
+    }
+
+    // A trainer with `count` Pokemon caught in order, so ids run 1..=count
+    async fn trainer_with_pokemon(count: u32) -> (TempDatabase, i64, Vec<i64>) {
+        let db = TempDatabase::new().await;
+        let trainer_id = db.create_trainer("Red").await.unwrap();
+        let mut ids = Vec::new();
+        for pokemon_id in 1..=count {
+            ids.push(
+                db.capture_pokemon(trainer_id, pokemon_id, None, 5, false)
+                    .await
+                    .unwrap(),
+            );
+        }
+        (db, trainer_id, ids)
+    }
+
+    async fn party_ids(db: &Database, trainer_id: i64) -> Vec<i64> {
+        let party = db.get_party(trainer_id).await.unwrap();
+        party.iter().map(|pokemon| pokemon.id).collect()
+    }
+
+    async fn location(db: &Database, trainer_id: i64, id: i64) -> Option<StorageLocation> {
+        let owned = db.get_trainer_pokemon(trainer_id).await.unwrap();
+        owned
+            .into_iter()
+            .find(|pokemon| pokemon.id == id)
+            .unwrap()
+            .location
+    }
+
+    #[tokio::test]
+    async fn captures_fill_the_party_then_boxes() {
+        let (db, trainer_id, ids) = trainer_with_pokemon(PARTY_SIZE as u32 + 2).await;
+
+        assert_eq!(party_ids(&db, trainer_id).await, ids[..6]);
+        let boxes = db.get_boxes(trainer_id).await.unwrap();
+        assert_eq!(boxes.len(), 1);
+        assert_eq!(boxes[0].name, "Box 1");
+        assert_eq!(
+            location(&db, trainer_id, ids[7]).await,
+            Some(StorageLocation::Box {
+                box_id: boxes[0].id,
+                position: 2
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn a_full_box_spills_into_a_new_one() {
+        let (db, trainer_id, ids) =
+            trainer_with_pokemon(PARTY_SIZE as u32 + BOX_CAPACITY + 1).await;
+
+        let boxes = db.get_boxes(trainer_id).await.unwrap();
+        assert_eq!(boxes.len(), 2);
+        let first = db.get_box_pokemon(boxes[0].id).await.unwrap();
+        assert_eq!(first.len(), BOX_CAPACITY as usize);
+        assert_eq!(
+            location(&db, trainer_id, *ids.last().unwrap()).await,
+            Some(StorageLocation::Box {
+                box_id: boxes[1].id,
+                position: 1
+            })
+        );
+
+        assert!(matches!(
+            db.deposit_pokemon(ids[0], boxes[0].id, None).await,
+            Err(StorageError::BoxFull(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn deposit_and_withdraw_keep_the_party_packed() {
+        let (db, trainer_id, ids) = trainer_with_pokemon(3).await;
+        let box_id = db.create_box(trainer_id, "Grass").await.unwrap();
+
+        db.deposit_pokemon(ids[0], box_id, None).await.unwrap();
+        assert_eq!(party_ids(&db, trainer_id).await, vec![ids[1], ids[2]]);
+        assert_eq!(
+            location(&db, trainer_id, ids[0]).await,
+            Some(StorageLocation::Box {
+                box_id,
+                position: 1
+            })
+        );
+
+        db.withdraw_pokemon(ids[0]).await.unwrap();
+        assert_eq!(
+            party_ids(&db, trainer_id).await,
+            vec![ids[1], ids[2], ids[0]]
+        );
+        assert!(db.get_box_pokemon(box_id).await.unwrap().is_empty());
+        assert!(matches!(
+            db.withdraw_pokemon(ids[0]).await,
+            Err(StorageError::InvalidLocation(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn the_last_party_member_stays() {
+        let (db, trainer_id, ids) = trainer_with_pokemon(1).await;
+        let box_id = db.create_box(trainer_id, "Box 1").await.unwrap();
+
+        assert!(matches!(
+            db.deposit_pokemon(ids[0], box_id, None).await,
+            Err(StorageError::LastPartyMember)
+        ));
+    }
+
+    #[tokio::test]
+    async fn moves_respect_slots_and_positions() {
+        let (db, trainer_id, ids) = trainer_with_pokemon(PARTY_SIZE as u32 + 1).await;
+        let boxed = ids[6];
+
+        // Reorder within the party
+        db.move_pokemon(ids[2], StorageLocation::Party { slot: 1 })
+            .await
+            .unwrap();
+        assert_eq!(
+            party_ids(&db, trainer_id).await,
+            vec![ids[2], ids[0], ids[1], ids[3], ids[4], ids[5]]
+        );
+
+        assert!(matches!(
+            db.move_pokemon(boxed, StorageLocation::Party { slot: 1 })
+                .await,
+            Err(StorageError::PartyFull)
+        ));
+
+        let box_id = db.get_boxes(trainer_id).await.unwrap()[0].id;
+        assert!(matches!(
+            db.move_pokemon(
+                ids[0],
+                StorageLocation::Box {
+                    box_id,
+                    position: 1
+                }
+            )
+            .await,
+            Err(StorageError::PositionTaken { .. })
+        ));
+        assert!(matches!(
+            db.move_pokemon(
+                ids[0],
+                StorageLocation::Box {
+                    box_id,
+                    position: BOX_CAPACITY + 1
+                }
+            )
+            .await,
+            Err(StorageError::InvalidLocation(_))
+        ));
+
+        db.move_pokemon(
+            ids[0],
+            StorageLocation::Box {
+                box_id,
+                position: 5,
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            party_ids(&db, trainer_id).await.len(),
+            PARTY_SIZE as usize - 1
+        );
+        db.move_pokemon(boxed, StorageLocation::Party { slot: 6 })
+            .await
+            .unwrap();
+        assert_eq!(party_ids(&db, trainer_id).await[5], boxed);
+    }
+
+    #[tokio::test]
+    async fn swaps_exchange_locations() {
+        let (db, trainer_id, ids) = trainer_with_pokemon(PARTY_SIZE as u32 + 1).await;
+        let boxed = ids[6];
+        let boxed_location = location(&db, trainer_id, boxed).await;
+
+        db.swap_pokemon(ids[0], boxed).await.unwrap();
+        assert_eq!(party_ids(&db, trainer_id).await[0], boxed);
+        assert_eq!(location(&db, trainer_id, ids[0]).await, boxed_location);
+
+        let rival = db.create_trainer("Blue").await.unwrap();
+        let rival_pokemon = db.capture_pokemon(rival, 4, None, 5, false).await.unwrap();
+        assert!(matches!(
+            db.swap_pokemon(ids[1], rival_pokemon).await,
+            Err(StorageError::InvalidLocation(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn only_empty_boxes_are_deleted() {
+        let (db, trainer_id, ids) = trainer_with_pokemon(2).await;
+        let box_id = db.create_box(trainer_id, "Box 1").await.unwrap();
+        db.deposit_pokemon(ids[0], box_id, Some(3)).await.unwrap();
+
+        assert!(matches!(
+            db.delete_box(box_id).await,
+            Err(StorageError::BoxNotEmpty(_))
+        ));
+        db.withdraw_pokemon(ids[0]).await.unwrap();
+        db.delete_box(box_id).await.unwrap();
+        assert!(db.get_boxes(trainer_id).await.unwrap().is_empty());
+    }
 }
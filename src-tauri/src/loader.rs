@@ -0,0 +1,469 @@
+use crate::source::SourceError;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::future::Future;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinSet;
+use tokio::time::Instant;
+
+/// How hard a bulk load may push its source
+#[derive(Debug, Clone)]
+pub struct LoaderOptions {
+    /// Pokemon fetched at the same time
+    pub concurrency: usize,
+    /// Tries per Pokemon, including the first
+    pub max_attempts: u32,
+    /// Wait before the first retry; doubles on each further retry
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for LoaderOptions {
+    fn default() -> Self {
+        LoaderOptions {
+            concurrency: 8,
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+/// A Pokemon that could not be loaded, kept so the load can be retried later
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailedLoad {
+    pub pokemon_id: u32,
+    pub error: String,
+    pub attempts: u32,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct LoadReport {
+    pub loaded: Vec<u32>,
+    pub failed: Vec<FailedLoad>,
+    /// Stopped early; Pokemon not yet tried are left for the next load
+    pub cancelled: bool,
+}
+
+/// Sent after each Pokemon finishes, successfully or not
+#[derive(Debug, Clone, Serialize)]
+pub struct LoadProgress {
+    pub loaded: u32,
+    pub failed: u32,
+    pub total: u32,
+    /// Estimated seconds left, from the pace so far
+    pub eta_seconds: u64,
+}
+
+/// Stops running bulk loads. Each load takes its own token as it starts, and a cancel
+/// reaches the loads holding one at that moment, never a load started afterwards.
+#[derive(Default)]
+pub struct LoadCancellation {
+    running: Mutex<Vec<Weak<watch::Sender<bool>>>>,
+}
+
+impl LoadCancellation {
+    /// Register a new load; it can be cancelled until its token is dropped
+    pub fn start(&self) -> LoadToken {
+        let sender = Arc::new(watch::Sender::new(false));
+        let mut running = self.running.lock().unwrap_or_else(|e| e.into_inner());
+        running.retain(|token| token.strong_count() > 0);
+        running.push(Arc::downgrade(&sender));
+        LoadToken { sender }
+    }
+
+    pub fn cancel(&self) {
+        let running = self.running.lock().unwrap_or_else(|e| e.into_inner());
+        for sender in running.iter().filter_map(Weak::upgrade) {
+            sender.send_replace(true);
+        }
+    }
+}
+
+/// One load's view of its cancellation
+pub struct LoadToken {
+    sender: Arc<watch::Sender<bool>>,
+}
+
+impl LoadToken {
+    pub fn subscribe(&self) -> watch::Receiver<bool> {
+        self.sender.subscribe()
+    }
+}
+
+// Work shared by the workers: the ids still to load, when a rate limit lifts,
+// and counts for progress reports
+struct LoadQueue {
+    ids: Mutex<VecDeque<u32>>,
+    paused_until: Mutex<Option<Instant>>,
+    cancelled: watch::Receiver<bool>,
+    total: u32,
+    loaded: AtomicU32,
+    failed: AtomicU32,
+    started: Instant,
+}
+
+impl LoadQueue {
+    fn next(&self) -> Option<u32> {
+        if self.is_cancelled() {
+            return None;
+        }
+        self.ids
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .pop_front()
+    }
+
+    fn is_cancelled(&self) -> bool {
+        *self.cancelled.borrow()
+    }
+
+    // Hold every worker back until the source is ready again
+    fn pause_for(&self, delay: Duration) {
+        let until = Instant::now() + delay;
+        let mut paused_until = self.paused_until.lock().unwrap_or_else(|e| e.into_inner());
+        if paused_until.is_none_or(|current| current < until) {
+            *paused_until = Some(until);
+        }
+    }
+
+    async fn wait_until_ready(&self) {
+        let paused_until = *self.paused_until.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(until) = paused_until {
+            self.sleep(until.saturating_duration_since(Instant::now()))
+                .await;
+        }
+    }
+
+    // Sleep, waking early if the load is cancelled
+    async fn sleep(&self, delay: Duration) {
+        let mut cancelled = self.cancelled.clone();
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => {}
+            _ = cancelled.wait_for(|cancelled| *cancelled) => {}
+        }
+    }
+
+    fn finish(&self, succeeded: bool) -> LoadProgress {
+        let counter = if succeeded {
+            &self.loaded
+        } else {
+            &self.failed
+        };
+        counter.fetch_add(1, Ordering::SeqCst);
+
+        let loaded = self.loaded.load(Ordering::SeqCst);
+        let failed = self.failed.load(Ordering::SeqCst);
+        let done = loaded + failed;
+        let remaining = self.total.saturating_sub(done);
+        let seconds_each = self.started.elapsed().as_secs_f64() / done as f64;
+
+        LoadProgress {
+            loaded,
+            failed,
+            total: self.total,
+            eta_seconds: (seconds_each * remaining as f64).round() as u64,
+        }
+    }
+}
+
+/// Load every id with at most `options.concurrency` loads in flight. Transient failures
+/// are retried with exponential backoff; a rate limit pauses all workers, for as long
+/// as the source asks if it says. Once `cancelled` turns true, loads in flight finish
+/// but nothing new starts and nothing is retried.
+pub async fn load_all<F, Fut, P>(
+    ids: Vec<u32>,
+    options: &LoaderOptions,
+    cancelled: watch::Receiver<bool>,
+    on_progress: P,
+    load: F,
+) -> LoadReport
+where
+    F: Fn(u32) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<(), SourceError>> + Send + 'static,
+    P: Fn(LoadProgress) + Send + Sync + 'static,
+{
+    let queue = Arc::new(LoadQueue {
+        total: ids.len() as u32,
+        ids: Mutex::new(ids.into()),
+        paused_until: Mutex::new(None),
+        cancelled,
+        loaded: AtomicU32::new(0),
+        failed: AtomicU32::new(0),
+        started: Instant::now(),
+    });
+    let load = Arc::new(load);
+    let on_progress = Arc::new(on_progress);
+
+    let mut workers = JoinSet::new();
+    for _ in 0..options.concurrency.max(1) {
+        let queue = queue.clone();
+        let load = load.clone();
+        let on_progress = on_progress.clone();
+        let options = options.clone();
+        workers.spawn(async move {
+            let mut report = LoadReport::default();
+            while let Some(id) = queue.next() {
+                match load_with_retries(&queue, &options, &*load, id).await {
+                    Ok(()) => {
+                        report.loaded.push(id);
+                        on_progress(queue.finish(true));
+                    }
+                    // Abandoned at cancellation rather than failed; the next load tries again
+                    Err(_) if queue.is_cancelled() => {}
+                    Err(failure) => {
+                        report.failed.push(failure);
+                        on_progress(queue.finish(false));
+                    }
+                }
+            }
+            report
+        });
+    }
+
+    let mut report = LoadReport::default();
+    while let Some(result) = workers.join_next().await {
+        match result {
+            Ok(worker_report) => {
+                report.loaded.extend(worker_report.loaded);
+                report.failed.extend(worker_report.failed);
+            }
+            Err(e) => eprintln!("Bulk load worker stopped: {}", e),
+        }
+    }
+    report.loaded.sort_unstable();
+    report
+        .failed
+        .sort_unstable_by_key(|failure| failure.pokemon_id);
+    report.cancelled = queue.is_cancelled();
+    report
+}
+
+async fn load_with_retries<F, Fut>(
+    queue: &LoadQueue,
+    options: &LoaderOptions,
+    load: &F,
+    id: u32,
+) -> Result<(), FailedLoad>
+where
+    F: Fn(u32) -> Fut,
+    Fut: Future<Output = Result<(), SourceError>>,
+{
+    let mut attempts = 0;
+    loop {
+        queue.wait_until_ready().await;
+        // The pause may have ended early because the load was cancelled
+        if queue.is_cancelled() {
+            return Err(FailedLoad {
+                pokemon_id: id,
+                error: "Load cancelled".to_string(),
+                attempts,
+            });
+        }
+        attempts += 1;
+
+        let error = match load(id).await {
+            Ok(()) => return Ok(()),
+            Err(e) => e,
+        };
+        if !error.is_transient() || attempts >= options.max_attempts || queue.is_cancelled() {
+            return Err(FailedLoad {
+                pokemon_id: id,
+                error: error.to_string(),
+                attempts,
+            });
+        }
+
+        let delay = error
+            .retry_after()
+            .unwrap_or_else(|| backoff(options, attempts));
+        match error {
+            SourceError::RateLimited { .. } => queue.pause_for(delay),
+            _ => queue.sleep(delay).await,
+        }
+    }
+}
+
+// Exponential backoff with jitter, so workers that failed together do not retry together
+fn backoff(options: &LoaderOptions, attempts: u32) -> Duration {
+    let delay = options
+        .initial_backoff
+        .saturating_mul(2u32.saturating_pow(attempts - 1))
+        .min(options.max_backoff);
+    delay.mul_f64(rand::thread_rng().gen_range(0.5..=1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn quick_options() -> LoaderOptions {
+        LoaderOptions {
+            concurrency: 2,
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(5),
+        }
+    }
+
+    #[tokio::test]
+    async fn transient_failures_are_retried() {
+        // Each id fails as many times as its value says, then loads
+        let attempts = Arc::new(Mutex::new(HashMap::<u32, u32>::new()));
+        let counter = attempts.clone();
+        let cancellation = LoadCancellation::default();
+        let token = cancellation.start();
+
+        let report = load_all(
+            vec![0, 1, 2, 3],
+            &quick_options(),
+            token.subscribe(),
+            |_| {},
+            move |id| {
+                let counter = counter.clone();
+                async move {
+                    let mut counts = counter.lock().unwrap();
+                    let count = counts.entry(id).or_default();
+                    *count += 1;
+                    if *count > id {
+                        Ok(())
+                    } else {
+                        Err(SourceError::Unavailable(format!("{} not yet", id)))
+                    }
+                }
+            },
+        )
+        .await;
+
+        assert_eq!(report.loaded, vec![0, 1, 2]);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].pokemon_id, 3);
+        assert_eq!(report.failed[0].attempts, 3);
+        assert!(!report.cancelled);
+    }
+
+    #[tokio::test]
+    async fn permanent_failures_are_not_retried() {
+        let cancellation = LoadCancellation::default();
+        let token = cancellation.start();
+
+        let report = load_all(
+            vec![7],
+            &quick_options(),
+            token.subscribe(),
+            |_| {},
+            |id| async move { Err(SourceError::NotFound(format!("{} is missing", id))) },
+        )
+        .await;
+
+        assert!(report.loaded.is_empty());
+        assert_eq!(report.failed[0].attempts, 1);
+        assert_eq!(report.failed[0].error, "7 is missing");
+    }
+
+    #[tokio::test]
+    async fn progress_counts_every_pokemon() {
+        let progress = Arc::new(Mutex::new(Vec::new()));
+        let recorded = progress.clone();
+        let cancellation = LoadCancellation::default();
+        let token = cancellation.start();
+
+        load_all(
+            (1..=5).collect(),
+            &quick_options(),
+            token.subscribe(),
+            move |update| recorded.lock().unwrap().push(update),
+            |id| async move {
+                if id == 3 {
+                    Err(SourceError::Invalid("bad data".to_string()))
+                } else {
+                    Ok(())
+                }
+            },
+        )
+        .await;
+
+        let progress = progress.lock().unwrap();
+        assert_eq!(progress.len(), 5);
+        let last = progress.iter().max_by_key(|p| p.loaded + p.failed).unwrap();
+        assert_eq!((last.loaded, last.failed, last.total), (4, 1, 5));
+    }
+
+    #[tokio::test]
+    async fn a_cancelled_load_starts_nothing_new() {
+        let cancellation = LoadCancellation::default();
+        let token = cancellation.start();
+        cancellation.cancel();
+
+        let report = load_all(
+            (1..=10).collect(),
+            &quick_options(),
+            token.subscribe(),
+            |_| {},
+            |_| async { Ok(()) },
+        )
+        .await;
+
+        assert!(report.cancelled);
+        assert!(report.loaded.is_empty());
+        assert!(report.failed.is_empty());
+    }
+
+    #[tokio::test]
+    async fn cancelling_during_a_rate_limit_pause_loads_nothing_more() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let counter = calls.clone();
+        let cancellation = Arc::new(LoadCancellation::default());
+        let token = cancellation.start();
+        let options = LoaderOptions {
+            concurrency: 1,
+            ..quick_options()
+        };
+
+        let canceller = cancellation.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            canceller.cancel();
+        });
+        let report = load_all(
+            vec![1, 2],
+            &options,
+            token.subscribe(),
+            |_| {},
+            move |_| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                async {
+                    Err(SourceError::RateLimited {
+                        message: "slow down".to_string(),
+                        retry_after: Some(Duration::from_secs(60)),
+                    })
+                }
+            },
+        )
+        .await;
+
+        assert!(report.cancelled);
+        assert!(report.failed.is_empty());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn cancel_reaches_only_running_loads() {
+        let cancellation = LoadCancellation::default();
+        let running = cancellation.start();
+        cancellation.cancel();
+        let later = cancellation.start();
+
+        assert!(*running.subscribe().borrow());
+        assert!(!*later.subscribe().borrow());
+
+        drop(running);
+        cancellation.cancel();
+        assert!(*later.subscribe().borrow());
+    }
+}
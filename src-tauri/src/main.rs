@@ -4,22 +4,89 @@
 mod commands;
 mod converter;
 mod database;
+mod loader;
+mod migrations;
 mod pokemon;
+mod source;
+#[cfg(test)]
+mod test_support;
+mod type_chart;
 
 use commands::*;
-use database::Database;
+use database::{Database, DatabaseStatus};
+use loader::LoadCancellation;
+use source::{HttpSource, SharedSource, SourceConfig};
+use std::path::Path;
+use std::sync::Arc;
 use tauri::Manager;
 
+const DATABASE_FILE_NAME: &str = "darkcaves-dragonites.db";
+
+// Open the on-disk database in the app data directory. If that is not possible,
+// fall back to an in-memory database so the app still runs, and report why in the
+// status so the frontend can warn that nothing will survive a restart.
+async fn open_database(path: Option<&Path>) -> Result<(Database, DatabaseStatus), sqlx::Error> {
+    let error = match path {
+        Some(path) => match open_database_file(path).await {
+            Ok(database) => {
+                println!("Database opened at {}", path.display());
+                let status = DatabaseStatus {
+                    persistent: true,
+                    error: None,
+                };
+                return Ok((database, status));
+            }
+            Err(e) => format!("Failed to open database at {}: {}", path.display(), e),
+        },
+        None => "No app data directory available on this platform".to_string(),
+    };
+
+    eprintln!("{}", error);
+    eprintln!("WARNING: falling back to an in-memory database; data will be lost on exit");
+    let status = DatabaseStatus {
+        persistent: false,
+        error: Some(error),
+    };
+    Ok((Database::in_memory().await?, status))
+}
+
+async fn open_database_file(path: &Path) -> Result<Database, sqlx::Error> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    Database::open(path).await
+}
+
+// Pick the Pokemon data source from the environment. A bad fixture file should
+// not stop the app, so fall back to the public PokeAPI.
+fn pokemon_source() -> SharedSource {
+    let config = SourceConfig::from_env();
+    let source = config.build().unwrap_or_else(|e| {
+        eprintln!("Failed to set up {:?}: {}; using PokeAPI", config, e);
+        Arc::new(HttpSource::pokeapi())
+    });
+
+    println!("Fetching Pokemon data from {}", source.describe());
+    source
+}
+
 fn main() {
     tauri::Builder::default()
         .setup(|app| {
-            // Use a simple in-memory database for now to avoid file permission issues
+            let database_path = app
+                .path_resolver()
+                .app_data_dir()
+                .map(|dir| dir.join(DATABASE_FILE_NAME));
+
+            app.manage(pokemon_source());
+            app.manage(LoadCancellation::default());
+
             let rt = tokio::runtime::Runtime::new().unwrap();
             rt.block_on(async {
-                match Database::new("sqlite::memory:").await {
-                    Ok(database) => {
+                match open_database(database_path.as_deref()).await {
+                    Ok((database, status)) => {
                         app.manage(database);
-                        println!("In-memory database initialized successfully");
+                        app.manage(status);
                     }
                     Err(e) => {
                         eprintln!("Failed to initialize database: {}", e);
@@ -39,8 +106,47 @@ fn main() {
             get_pokemon_batch,
             get_pokemon_list,
             get_pokemon_list_improved,
+            query_pokemon,
             initialize_pokemon_data,
-            ensure_pokemon_database_initialized
+            initialize_all_pokemon_data,
+            import_api_data,
+            get_failed_pokemon_loads,
+            retry_failed_pokemon_loads,
+            cancel_pokemon_load,
+            refresh_generation_data,
+            get_generations,
+            get_pokedexes,
+            get_dex_entries,
+            ensure_pokemon_database_initialized,
+            convert_pokemon_to_dnd,
+            convert_user_pokemon_to_dnd,
+            get_conversion_profiles,
+            save_conversion_profile,
+            delete_conversion_profile,
+            create_trainer,
+            get_trainer,
+            capture_pokemon,
+            get_trainer_pokemon,
+            update_pokemon_level,
+            award_experience,
+            evolve_pokemon,
+            get_evolution_chain,
+            get_species,
+            release_pokemon,
+            update_pokemon_customization,
+            get_party,
+            get_boxes,
+            get_box_pokemon,
+            create_box,
+            rename_box,
+            delete_box,
+            reorder_boxes,
+            move_pokemon,
+            swap_pokemon,
+            deposit_pokemon,
+            withdraw_pokemon,
+            export_stat_block,
+            get_database_status
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
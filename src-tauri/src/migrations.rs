@@ -0,0 +1,496 @@
+use sqlx::{sqlite::SqlitePool, Row};
+
+// Numbered schema migrations. Append new ones to the end; never edit or reorder a
+// migration that has shipped, since existing database files have already run it.
+pub struct Migration {
+    pub version: i64,
+    pub description: &'static str,
+    pub statements: &'static [&'static str],
+}
+
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        // IF NOT EXISTS so databases created before versioning adopt this baseline as-is
+        description: "initial schema",
+        statements: &[
+            r#"
+            CREATE TABLE IF NOT EXISTS trainers (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL,
+                created_date TEXT NOT NULL
+            )
+            "#,
+            r#"
+            CREATE TABLE IF NOT EXISTS user_pokemon (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                pokemon_id INTEGER NOT NULL,
+                nickname TEXT,
+                level INTEGER NOT NULL DEFAULT 1,
+                experience INTEGER NOT NULL DEFAULT 0,
+                captured_date TEXT NOT NULL,
+                is_shiny BOOLEAN NOT NULL DEFAULT FALSE,
+                trainer_id INTEGER NOT NULL,
+                FOREIGN KEY (trainer_id) REFERENCES trainers (id)
+            )
+            "#,
+            r#"
+            CREATE TABLE IF NOT EXISTS pokemon_cache (
+                id INTEGER PRIMARY KEY,
+                name TEXT NOT NULL,
+                data TEXT NOT NULL,
+                last_updated TEXT NOT NULL
+            )
+            "#,
+            r#"
+            CREATE TABLE IF NOT EXISTS move_cache (
+                name TEXT PRIMARY KEY,
+                data TEXT NOT NULL,
+                last_updated TEXT NOT NULL
+            )
+            "#,
+            r#"
+            CREATE TABLE IF NOT EXISTS conversion_profiles (
+                name TEXT PRIMARY KEY,
+                data TEXT NOT NULL,
+                last_updated TEXT NOT NULL
+            )
+            "#,
+        ],
+    },
+    Migration {
+        version: 2,
+        description: "normalized pokemon stats, generation and types",
+        statements: &[
+            "ALTER TABLE pokemon_cache ADD COLUMN hp INTEGER NOT NULL DEFAULT 0",
+            "ALTER TABLE pokemon_cache ADD COLUMN attack INTEGER NOT NULL DEFAULT 0",
+            "ALTER TABLE pokemon_cache ADD COLUMN defense INTEGER NOT NULL DEFAULT 0",
+            "ALTER TABLE pokemon_cache ADD COLUMN special_attack INTEGER NOT NULL DEFAULT 0",
+            "ALTER TABLE pokemon_cache ADD COLUMN special_defense INTEGER NOT NULL DEFAULT 0",
+            "ALTER TABLE pokemon_cache ADD COLUMN speed INTEGER NOT NULL DEFAULT 0",
+            "ALTER TABLE pokemon_cache ADD COLUMN base_stat_total INTEGER NOT NULL DEFAULT 0",
+            "ALTER TABLE pokemon_cache ADD COLUMN generation INTEGER",
+            r#"
+            CREATE TABLE pokemon_types (
+                pokemon_id INTEGER NOT NULL,
+                type_name TEXT NOT NULL,
+                slot INTEGER NOT NULL,
+                PRIMARY KEY (pokemon_id, slot),
+                FOREIGN KEY (pokemon_id) REFERENCES pokemon_cache (id) ON DELETE CASCADE
+            )
+            "#,
+            "CREATE INDEX idx_pokemon_types_type_name ON pokemon_types (type_name)",
+            "CREATE INDEX idx_pokemon_cache_name ON pokemon_cache (name)",
+            "CREATE INDEX idx_pokemon_cache_generation ON pokemon_cache (generation)",
+            "CREATE INDEX idx_pokemon_cache_base_stat_total ON pokemon_cache (base_stat_total)",
+            // Backfill rows cached before this migration from their JSON
+            r#"
+            UPDATE pokemon_cache SET
+                hp = json_extract(data, '$.base_stats.hp'),
+                attack = json_extract(data, '$.base_stats.attack'),
+                defense = json_extract(data, '$.base_stats.defense'),
+                special_attack = json_extract(data, '$.base_stats.special_attack'),
+                special_defense = json_extract(data, '$.base_stats.special_defense'),
+                speed = json_extract(data, '$.base_stats.speed'),
+                generation = CASE
+                    WHEN id <= 151 THEN 1
+                    WHEN id <= 251 THEN 2
+                    WHEN id <= 386 THEN 3
+                    WHEN id <= 493 THEN 4
+                    WHEN id <= 649 THEN 5
+                    WHEN id <= 721 THEN 6
+                    WHEN id <= 809 THEN 7
+                    WHEN id <= 905 THEN 8
+                    WHEN id <= 1025 THEN 9
+                END
+            "#,
+            r#"
+            UPDATE pokemon_cache SET
+                base_stat_total = hp + attack + defense + special_attack + special_defense + speed
+            "#,
+            r#"
+            INSERT OR IGNORE INTO pokemon_types (pokemon_id, type_name, slot)
+            SELECT pokemon_cache.id, json_extract(t.value, '$.name'), json_extract(t.value, '$.slot')
+            FROM pokemon_cache, json_each(pokemon_cache.data, '$.types') AS t
+            "#,
+        ],
+    },
+    Migration {
+        version: 3,
+        description: "pokemon abilities",
+        statements: &[
+            r#"
+            CREATE TABLE pokemon_abilities (
+                pokemon_id INTEGER NOT NULL,
+                ability_name TEXT NOT NULL,
+                is_hidden BOOLEAN NOT NULL DEFAULT FALSE,
+                slot INTEGER NOT NULL,
+                PRIMARY KEY (pokemon_id, slot),
+                FOREIGN KEY (pokemon_id) REFERENCES pokemon_cache (id) ON DELETE CASCADE
+            )
+            "#,
+            "CREATE INDEX idx_pokemon_abilities_ability_name ON pokemon_abilities (ability_name)",
+            r#"
+            INSERT OR IGNORE INTO pokemon_abilities (pokemon_id, ability_name, is_hidden, slot)
+            SELECT pokemon_cache.id, json_extract(a.value, '$.name'),
+                   json_extract(a.value, '$.is_hidden'), json_extract(a.value, '$.slot')
+            FROM pokemon_cache, json_each(pokemon_cache.data, '$.abilities') AS a
+            "#,
+        ],
+    },
+    Migration {
+        version: 4,
+        description: "full-text search index",
+        statements: &[
+            r#"
+            CREATE VIRTUAL TABLE pokemon_search USING fts5(
+                name,
+                types,
+                abilities,
+                flavor_text,
+                tokenize = 'unicode61 remove_diacritics 2',
+                prefix = '2 3'
+            )
+            "#,
+            r#"
+            CREATE TRIGGER pokemon_search_delete AFTER DELETE ON pokemon_cache
+            BEGIN
+                DELETE FROM pokemon_search WHERE rowid = old.id;
+            END
+            "#,
+            r#"
+            INSERT INTO pokemon_search (rowid, name, types, abilities, flavor_text)
+            SELECT pokemon_cache.id, pokemon_cache.name,
+                   (SELECT group_concat(type_name, ' ') FROM pokemon_types
+                    WHERE pokemon_types.pokemon_id = pokemon_cache.id),
+                   (SELECT group_concat(ability_name, ' ') FROM pokemon_abilities
+                    WHERE pokemon_abilities.pokemon_id = pokemon_cache.id),
+                   ''
+            FROM pokemon_cache
+            "#,
+        ],
+    },
+    Migration {
+        version: 5,
+        description: "party slots and storage boxes",
+        statements: &[
+            r#"
+            CREATE TABLE pokemon_boxes (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                trainer_id INTEGER NOT NULL,
+                name TEXT NOT NULL,
+                position INTEGER NOT NULL,
+                FOREIGN KEY (trainer_id) REFERENCES trainers (id) ON DELETE CASCADE
+            )
+            "#,
+            "CREATE INDEX idx_pokemon_boxes_trainer ON pokemon_boxes (trainer_id, position)",
+            "ALTER TABLE user_pokemon ADD COLUMN party_slot INTEGER",
+            "ALTER TABLE user_pokemon ADD COLUMN box_id INTEGER REFERENCES pokemon_boxes (id)",
+            "ALTER TABLE user_pokemon ADD COLUMN box_position INTEGER",
+            // Number each trainer's existing Pokemon by capture order; the first six form the party
+            r#"
+            UPDATE user_pokemon SET party_slot = (
+                SELECT ranked.capture_order FROM (
+                    SELECT id, ROW_NUMBER() OVER (
+                        PARTITION BY trainer_id ORDER BY captured_date, id
+                    ) AS capture_order
+                    FROM user_pokemon
+                ) AS ranked
+                WHERE ranked.id = user_pokemon.id
+            )
+            "#,
+            // The rest fill boxes of 30 (BOX_CAPACITY) in the same order, as many as needed
+            r#"
+            INSERT INTO pokemon_boxes (trainer_id, name, position)
+            WITH RECURSIVE box_numbers (trainer_id, number, needed) AS (
+                SELECT trainer_id, 1, (MAX(party_slot) - 7) / 30 + 1
+                FROM user_pokemon WHERE party_slot > 6 GROUP BY trainer_id
+                UNION ALL
+                SELECT trainer_id, number + 1, needed FROM box_numbers WHERE number < needed
+            )
+            SELECT trainer_id, 'Box ' || number, number FROM box_numbers
+            "#,
+            r#"
+            UPDATE user_pokemon SET
+                box_id = (
+                    SELECT pokemon_boxes.id FROM pokemon_boxes
+                    WHERE pokemon_boxes.trainer_id = user_pokemon.trainer_id
+                      AND pokemon_boxes.position = (user_pokemon.party_slot - 7) / 30 + 1
+                ),
+                box_position = (party_slot - 7) % 30 + 1,
+                party_slot = NULL
+            WHERE party_slot > 6
+            "#,
+            r#"
+            CREATE UNIQUE INDEX idx_user_pokemon_party_slot ON user_pokemon (trainer_id, party_slot)
+            WHERE party_slot IS NOT NULL
+            "#,
+            r#"
+            CREATE UNIQUE INDEX idx_user_pokemon_box_position ON user_pokemon (box_id, box_position)
+            WHERE box_id IS NOT NULL
+            "#,
+        ],
+    },
+    Migration {
+        version: 6,
+        description: "owned pokemon customization",
+        // JSON-encoded PokemonCustomization; NULL for Pokemon caught before this existed
+        statements: &["ALTER TABLE user_pokemon ADD COLUMN customization TEXT"],
+    },
+    Migration {
+        version: 7,
+        description: "species cache",
+        statements: &[r#"
+            CREATE TABLE species_cache (
+                id INTEGER PRIMARY KEY,
+                name TEXT NOT NULL,
+                data TEXT NOT NULL,
+                last_updated TEXT NOT NULL
+            )
+            "#],
+    },
+    Migration {
+        version: 8,
+        description: "evolution chain cache",
+        statements: &[r#"
+            CREATE TABLE evolution_chain_cache (
+                id INTEGER PRIMARY KEY,
+                data TEXT NOT NULL,
+                last_updated TEXT NOT NULL
+            )
+            "#],
+    },
+    Migration {
+        version: 9,
+        // Species records cached before this lack the newer fields; refetch them
+        description: "full species records",
+        statements: &["DELETE FROM species_cache"],
+    },
+    Migration {
+        version: 10,
+        description: "failed bulk loads",
+        statements: &[r#"
+            CREATE TABLE failed_pokemon_loads (
+                pokemon_id INTEGER PRIMARY KEY,
+                error TEXT NOT NULL,
+                attempts INTEGER NOT NULL,
+                last_attempt TEXT NOT NULL
+            )
+            "#],
+    },
+    Migration {
+        version: 11,
+        description: "generations and pokedexes",
+        statements: &[
+            r#"
+            CREATE TABLE generations (
+                id INTEGER PRIMARY KEY,
+                name TEXT NOT NULL,
+                main_region TEXT NOT NULL
+            )
+            "#,
+            r#"
+            CREATE TABLE species_generations (
+                species_id INTEGER PRIMARY KEY,
+                generation INTEGER NOT NULL
+            )
+            "#,
+            "CREATE INDEX idx_species_generations_generation ON species_generations (generation)",
+            r#"
+            CREATE TABLE pokedexes (
+                name TEXT PRIMARY KEY,
+                id INTEGER NOT NULL,
+                region TEXT,
+                is_main_series BOOLEAN NOT NULL
+            )
+            "#,
+            r#"
+            CREATE TABLE pokedex_entries (
+                pokedex TEXT NOT NULL,
+                entry_number INTEGER NOT NULL,
+                species_id INTEGER NOT NULL,
+                PRIMARY KEY (pokedex, entry_number)
+            )
+            "#,
+            "CREATE INDEX idx_pokedex_entries_species ON pokedex_entries (species_id)",
+        ],
+    },
+    Migration {
+        version: 12,
+        description: "imported pokemon",
+        // Set for Pokemon read from a local api-data dump; they never expire
+        statements: &[
+            "ALTER TABLE pokemon_cache ADD COLUMN imported BOOLEAN NOT NULL DEFAULT FALSE",
+        ],
+    },
+    Migration {
+        version: 13,
+        description: "camelCase conversion profiles",
+        // Profiles are stored as serialized JSON, which now uses the frontend's field names
+        statements: &[r#"
+            UPDATE conversion_profiles SET data = json_object(
+                'name', json_extract(data, '$.name'),
+                'levelMultiplier', json_extract(data, '$.level_multiplier'),
+                'abilityScoreScale', json_extract(data, '$.ability_score_scale'),
+                'statScaling', json_object(
+                    'hpMultiplier', json_extract(data, '$.stat_scaling.hp_multiplier'),
+                    'attackMultiplier', json_extract(data, '$.stat_scaling.attack_multiplier'),
+                    'defenseMultiplier', json_extract(data, '$.stat_scaling.defense_multiplier'),
+                    'speedMultiplier', json_extract(data, '$.stat_scaling.speed_multiplier'),
+                    'minAbilityScore', json_extract(data, '$.stat_scaling.min_ability_score'),
+                    'maxAbilityScore', json_extract(data, '$.stat_scaling.max_ability_score')
+                ),
+                'damageTypes', json(json_extract(data, '$.damage_types')),
+                'hitPointMethod', COALESCE(json_extract(data, '$.hit_point_method'), 'average')
+            )
+            WHERE json_valid(data) AND json_extract(data, '$.stat_scaling') IS NOT NULL
+            "#],
+    },
+    Migration {
+        version: 14,
+        description: "type efficacy",
+        // Imported type chart; empty until one is imported, when the built-in chart applies
+        statements: &[r#"
+            CREATE TABLE type_efficacy (
+                attacking_type TEXT NOT NULL,
+                defending_type TEXT NOT NULL,
+                multiplier REAL NOT NULL,
+                PRIMARY KEY (attacking_type, defending_type)
+            )
+            "#],
+    },
+    Migration {
+        version: 15,
+        description: "search index triggers",
+        // Keep the search index in step with every write to the cache, not just cache_pokemon.
+        // Flavor text is only set afterwards, so a refresh leaves it alone.
+        statements: &[
+            r#"
+            CREATE TRIGGER pokemon_search_insert AFTER INSERT ON pokemon_cache
+            BEGIN
+                UPDATE pokemon_search SET
+                    name = new.name,
+                    types = (SELECT group_concat(json_extract(value, '$.name'), ' ')
+                             FROM json_each(new.data, '$.types')),
+                    abilities = (SELECT group_concat(json_extract(value, '$.name'), ' ')
+                                 FROM json_each(new.data, '$.abilities'))
+                WHERE rowid = new.id;
+                INSERT INTO pokemon_search (rowid, name, types, abilities, flavor_text)
+                SELECT new.id, new.name,
+                       (SELECT group_concat(json_extract(value, '$.name'), ' ')
+                        FROM json_each(new.data, '$.types')),
+                       (SELECT group_concat(json_extract(value, '$.name'), ' ')
+                        FROM json_each(new.data, '$.abilities')),
+                       ''
+                WHERE NOT EXISTS (SELECT 1 FROM pokemon_search WHERE rowid = new.id);
+            END
+            "#,
+            r#"
+            CREATE TRIGGER pokemon_search_update AFTER UPDATE OF name, data ON pokemon_cache
+            BEGIN
+                UPDATE pokemon_search SET
+                    name = new.name,
+                    types = (SELECT group_concat(json_extract(value, '$.name'), ' ')
+                             FROM json_each(new.data, '$.types')),
+                    abilities = (SELECT group_concat(json_extract(value, '$.name'), ' ')
+                                 FROM json_each(new.data, '$.abilities'))
+                WHERE rowid = new.id;
+                INSERT INTO pokemon_search (rowid, name, types, abilities, flavor_text)
+                SELECT new.id, new.name,
+                       (SELECT group_concat(json_extract(value, '$.name'), ' ')
+                        FROM json_each(new.data, '$.types')),
+                       (SELECT group_concat(json_extract(value, '$.name'), ' ')
+                        FROM json_each(new.data, '$.abilities')),
+                       ''
+                WHERE NOT EXISTS (SELECT 1 FROM pokemon_search WHERE rowid = new.id);
+            END
+            "#,
+            // Rows written straight to the cache before now
+            r#"
+            INSERT INTO pokemon_search (rowid, name, types, abilities, flavor_text)
+            SELECT pokemon_cache.id, pokemon_cache.name,
+                   (SELECT group_concat(json_extract(value, '$.name'), ' ')
+                    FROM json_each(pokemon_cache.data, '$.types')),
+                   (SELECT group_concat(json_extract(value, '$.name'), ' ')
+                    FROM json_each(pokemon_cache.data, '$.abilities')),
+                   ''
+            FROM pokemon_cache
+            WHERE pokemon_cache.id NOT IN (SELECT rowid FROM pokemon_search)
+            "#,
+        ],
+    },
+    Migration {
+        version: 16,
+        description: "stored challenge ratings",
+        // Filled in from the JSON when the database is opened
+        statements: &[
+            "ALTER TABLE pokemon_cache ADD COLUMN challenge_rating REAL",
+            "CREATE INDEX idx_pokemon_cache_challenge_rating ON pokemon_cache (challenge_rating)",
+        ],
+    },
+];
+
+/// Newest schema version this build of the app understands
+pub fn latest_version() -> i64 {
+    MIGRATIONS.last().map(|m| m.version).unwrap_or(0)
+}
+
+/// Bring the database up to the latest schema version, one transaction per migration.
+/// Refuses to touch a database written by a newer version of the app.
+pub async fn run(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS schema_version (
+            version INTEGER NOT NULL,
+            applied_date TEXT NOT NULL
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    let current = current_version(pool).await?;
+    if current > latest_version() {
+        return Err(sqlx::Error::Configuration(
+            format!(
+                "Database schema version {} is newer than this app supports ({}); please update the app",
+                current,
+                latest_version()
+            )
+            .into(),
+        ));
+    }
+
+    for migration in MIGRATIONS.iter().filter(|m| m.version > current) {
+        let mut tx = pool.begin().await?;
+
+        for statement in migration.statements {
+            sqlx::query(statement).execute(&mut *tx).await?;
+        }
+
+        sqlx::query("DELETE FROM schema_version")
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("INSERT INTO schema_version (version, applied_date) VALUES (?, ?)")
+            .bind(migration.version)
+            .bind(chrono::Utc::now().to_rfc3339())
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        println!(
+            "Applied database migration {}: {}",
+            migration.version, migration.description
+        );
+    }
+
+    Ok(())
+}
+
+async fn current_version(pool: &SqlitePool) -> Result<i64, sqlx::Error> {
+    let row = sqlx::query("SELECT MAX(version) as version FROM schema_version")
+        .fetch_one(pool)
+        .await?;
+
+    Ok(row.get::<Option<i64>, _>("version").unwrap_or(0))
+}
@@ -82,6 +82,180 @@ pub struct PokeApiResource {
     pub url: String,
 }
 
+impl PokeApiResource {
+    /// Id from the trailing segment of the resource URL
+    pub fn id(&self) -> Option<u32> {
+        self.url.split('/').nth_back(1)?.parse().ok()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PokeApiMoveResponse {
+    pub id: u32,
+    pub name: String,
+    pub accuracy: Option<u32>,
+    pub power: Option<u32>,
+    pub pp: Option<u32>,
+    pub priority: i32,
+    pub r#type: PokeApiResource,
+    pub damage_class: PokeApiResource,
+    pub target: PokeApiResource,
+    pub effect_chance: Option<u32>,
+    pub effect_entries: Vec<PokeApiEffectEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PokeApiEffectEntry {
+    pub effect: String,
+    pub short_effect: String,
+    pub language: PokeApiResource,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PokeApiSpeciesResponse {
+    pub id: u32,
+    pub name: String,
+    pub flavor_text_entries: Vec<PokeApiFlavorTextEntry>,
+    pub genera: Vec<PokeApiGenus>,
+    pub capture_rate: u32,
+    pub base_happiness: Option<u32>,
+    pub growth_rate: PokeApiResource,
+    pub habitat: Option<PokeApiResource>,
+    pub egg_groups: Vec<PokeApiResource>,
+    pub gender_rate: i32,
+    pub is_legendary: bool,
+    pub is_mythical: bool,
+    pub evolves_from_species: Option<PokeApiResource>,
+    pub evolution_chain: Option<PokeApiUrl>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PokeApiGenus {
+    pub genus: String,
+    pub language: PokeApiResource,
+}
+
+// PokeAPI links to some resources, like evolution chains, by URL alone
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PokeApiUrl {
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PokeApiFlavorTextEntry {
+    pub flavor_text: String,
+    pub language: PokeApiResource,
+    pub version: PokeApiResource,
+}
+
+impl PokeApiSpeciesResponse {
+    /// Most recent English Pokedex entry, with the game's hard line breaks flattened
+    pub fn english_flavor_text(&self) -> Option<String> {
+        self.flavor_text_entries
+            .iter()
+            .rev()
+            .find(|entry| entry.language.name == "en")
+            .map(|entry| {
+                entry
+                    .flavor_text
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+    }
+
+    pub fn english_genus(&self) -> Option<String> {
+        self.genera
+            .iter()
+            .find(|entry| entry.language.name == "en")
+            .map(|entry| entry.genus.clone())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PokeApiEvolutionChainResponse {
+    pub id: u32,
+    pub chain: PokeApiChainLink,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PokeApiChainLink {
+    pub species: PokeApiResource,
+    pub evolution_details: Vec<PokeApiEvolutionDetail>,
+    pub evolves_to: Vec<PokeApiChainLink>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PokeApiEvolutionDetail {
+    pub trigger: PokeApiResource,
+    pub min_level: Option<u32>,
+    pub item: Option<PokeApiResource>,
+    pub held_item: Option<PokeApiResource>,
+    pub known_move: Option<PokeApiResource>,
+    pub known_move_type: Option<PokeApiResource>,
+    pub location: Option<PokeApiResource>,
+    pub min_happiness: Option<u32>,
+    pub min_affection: Option<u32>,
+    pub min_beauty: Option<u32>,
+    #[serde(default)]
+    pub time_of_day: String,
+    pub gender: Option<u8>,
+    pub party_species: Option<PokeApiResource>,
+    pub party_type: Option<PokeApiResource>,
+    pub trade_species: Option<PokeApiResource>,
+    pub relative_physical_stats: Option<i32>,
+    #[serde(default)]
+    pub needs_overworld_rain: bool,
+    #[serde(default)]
+    pub turn_upside_down: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PokeApiGenerationResponse {
+    pub id: u32,
+    pub name: String,
+    pub main_region: PokeApiResource,
+    pub pokemon_species: Vec<PokeApiResource>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PokeApiTypeResponse {
+    pub id: u32,
+    pub name: String,
+    pub damage_relations: PokeApiDamageRelations,
+    #[serde(default)]
+    pub pokemon: Vec<PokeApiTypePokemon>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PokeApiTypePokemon {
+    pub pokemon: PokeApiResource,
+    pub slot: u8,
+}
+
+// Only the attacking side is kept; the "_from" lists mirror it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PokeApiDamageRelations {
+    pub double_damage_to: Vec<PokeApiResource>,
+    pub half_damage_to: Vec<PokeApiResource>,
+    pub no_damage_to: Vec<PokeApiResource>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PokeApiPokedexResponse {
+    pub id: u32,
+    pub name: String,
+    pub is_main_series: bool,
+    pub region: Option<PokeApiResource>,
+    pub pokemon_entries: Vec<PokeApiPokedexEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PokeApiPokedexEntry {
+    pub entry_number: u32,
+    pub pokemon_species: PokeApiResource,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PokeApiResponse<T> {
     pub count: u32,
@@ -94,6 +268,12 @@ pub struct PokeApiResponse<T> {
 pub struct Pokemon {
     pub id: u32,
     pub name: String,
+    /// In decimetres, as PokeAPI reports it
+    #[serde(default)]
+    pub height: u32,
+    /// In hectograms, as PokeAPI reports it
+    #[serde(default)]
+    pub weight: u32,
     pub base_stats: BaseStats,
     pub types: Vec<PokemonType>,
     pub moves: Vec<Move>,
@@ -101,7 +281,7 @@ pub struct Pokemon {
     pub sprites: Sprites,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct BaseStats {
     pub hp: u32,
     pub attack: u32,
@@ -125,6 +305,11 @@ pub struct Move {
     pub pp: u32,
     pub move_type: String,
     pub damage_class: String,
+    pub priority: i32,
+    pub target: String,
+    pub effect: Option<String>,
+    pub effect_chance: Option<u32>,
+    pub level_learned_at: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -134,7 +319,7 @@ pub struct Ability {
     pub slot: u32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Sprites {
     pub front_default: Option<String>,
     pub front_shiny: Option<String>,
@@ -149,6 +334,8 @@ impl Pokemon {
         Self {
             id,
             name,
+            height: 0,
+            weight: 0,
             base_stats: BaseStats::default(),
             types: Vec::new(),
             moves: Vec::new(),
@@ -157,8 +344,9 @@ impl Pokemon {
         }
     }
 
-    /// Convert from PokeAPI response to our internal Pokemon structure
-    pub fn from_pokeapi_response(response: PokeApiPokemonResponse) -> Self {
+    /// Convert from PokeAPI response to our internal Pokemon structure.
+    /// `moves` are the resolved entries for `response.level_up_moves()`.
+    pub fn from_pokeapi_response(response: PokeApiPokemonResponse, moves: Vec<Move>) -> Self {
         let base_stats = BaseStats {
             hp: response
                 .stats
@@ -217,21 +405,6 @@ impl Pokemon {
             })
             .collect();
 
-        // Take first 10 moves for simplicity (full move data would require additional API calls)
-        let moves = response
-            .moves
-            .iter()
-            .take(10)
-            .map(|m| Move {
-                name: m.r#move.name.clone(),
-                power: None,    // Would need additional API call to move endpoint
-                accuracy: None, // Would need additional API call to move endpoint
-                pp: 0,          // Would need additional API call to move endpoint
-                move_type: "normal".to_string(), // Would need additional API call
-                damage_class: "physical".to_string(), // Would need additional API call
-            })
-            .collect();
-
         let sprites = Sprites {
             front_default: response.sprites.front_default,
             front_shiny: response.sprites.front_shiny,
@@ -242,6 +415,8 @@ impl Pokemon {
         Self {
             id: response.id,
             name: response.name,
+            height: response.height,
+            weight: response.weight,
             base_stats,
             types,
             moves,
@@ -251,26 +426,917 @@ impl Pokemon {
     }
 }
 
-impl Default for BaseStats {
-    fn default() -> Self {
+impl PokeApiPokemonResponse {
+    /// Level-up learnset as (move name, level learned), using the most recent
+    /// version group that teaches each move by level, sorted by level.
+    pub fn level_up_moves(&self) -> Vec<(String, u32)> {
+        let mut learnset: Vec<(String, u32)> = self
+            .moves
+            .iter()
+            .filter_map(|m| {
+                m.version_group_details
+                    .iter()
+                    .rev()
+                    .find(|d| d.move_learn_method.name == "level-up")
+                    .map(|d| (m.r#move.name.clone(), d.level_learned_at))
+            })
+            .collect();
+
+        learnset.sort_by_key(|(_, level)| *level);
+        learnset
+    }
+}
+
+impl Move {
+    /// Convert a PokeAPI /move response into a Move learned at the given level
+    pub fn from_pokeapi_response(response: PokeApiMoveResponse, level_learned_at: u32) -> Self {
+        let effect = response
+            .effect_entries
+            .iter()
+            .find(|e| e.language.name == "en")
+            .map(|e| {
+                // PokeAPI templates the secondary effect chance into the text
+                let chance = response.effect_chance.unwrap_or(0).to_string();
+                e.short_effect.replace("$effect_chance", &chance)
+            });
+
         Self {
-            hp: 0,
-            attack: 0,
-            defense: 0,
-            special_attack: 0,
-            special_defense: 0,
-            speed: 0,
+            name: response.name,
+            power: response.power,
+            accuracy: response.accuracy,
+            pp: response.pp.unwrap_or(0),
+            move_type: response.r#type.name,
+            damage_class: response.damage_class.name,
+            priority: response.priority,
+            target: response.target.name,
+            effect,
+            effect_chance: response.effect_chance,
+            level_learned_at,
         }
     }
 }
 
-impl Default for Sprites {
-    fn default() -> Self {
+impl BaseStats {
+    pub fn total(&self) -> u32 {
+        self.hp
+            + self.attack
+            + self.defense
+            + self.special_attack
+            + self.special_defense
+            + self.speed
+    }
+}
+
+// Last National Dex number introduced in each generation (Gen 1..=9)
+const GENERATION_LAST_IDS: [u32; 9] = [151, 251, 386, 493, 649, 721, 809, 905, 1025];
+
+/// Generation a species was introduced in, from its National Dex number.
+/// Only a fallback until /generation has been ingested, since it stops at Gen 9.
+/// Alternate forms (PokeAPI ids 10001+) have no generation of their own.
+pub fn generation_for_national_dex_id(id: u32) -> Option<u32> {
+    GENERATION_LAST_IDS
+        .iter()
+        .position(|last_id| id <= *last_id)
+        .map(|index| index as u32 + 1)
+}
+
+/// A generation of games, with the species it introduced
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Generation {
+    pub id: u32,
+    pub name: String,
+    pub main_region: String,
+    pub species_ids: Vec<u32>,
+}
+
+impl Generation {
+    pub fn from_pokeapi_response(response: &PokeApiGenerationResponse) -> Self {
+        let mut species_ids: Vec<u32> = response
+            .pokemon_species
+            .iter()
+            .filter_map(PokeApiResource::id)
+            .collect();
+        species_ids.sort_unstable();
+
         Self {
-            front_default: None,
-            front_shiny: None,
-            back_default: None,
-            back_shiny: None,
+            id: response.id,
+            name: response.name.clone(),
+            main_region: response.main_region.name.clone(),
+            species_ids,
         }
     }
 }
+
+/// The National Dex or a regional one. Entries are kept apart, in `DexEntry`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Pokedex {
+    pub id: u32,
+    pub name: String,
+    pub region: Option<String>,
+    pub is_main_series: bool,
+}
+
+/// A species' number in one Pokedex
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DexEntry {
+    pub pokedex: String,
+    pub entry_number: u32,
+    pub species_id: u32,
+}
+
+impl Pokedex {
+    pub fn from_pokeapi_response(response: &PokeApiPokedexResponse) -> (Self, Vec<DexEntry>) {
+        let entries = response
+            .pokemon_entries
+            .iter()
+            .filter_map(|entry| {
+                Some(DexEntry {
+                    pokedex: response.name.clone(),
+                    entry_number: entry.entry_number,
+                    species_id: entry.pokemon_species.id()?,
+                })
+            })
+            .collect();
+        let pokedex = Self {
+            id: response.id,
+            name: response.name.clone(),
+            region: response.region.as_ref().map(|region| region.name.clone()),
+            is_main_series: response.is_main_series,
+        };
+        (pokedex, entries)
+    }
+}
+
+pub const MAX_LEVEL: u8 = 100;
+
+/// Experience curve of a species, named as PokeAPI's /growth-rate resources
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GrowthRate {
+    Fast,
+    Medium,
+    MediumSlow,
+    Slow,
+    SlowThenVeryFast,
+    FastThenVerySlow,
+}
+
+impl GrowthRate {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "fast" => Some(GrowthRate::Fast),
+            "medium" => Some(GrowthRate::Medium),
+            "medium-slow" => Some(GrowthRate::MediumSlow),
+            "slow" => Some(GrowthRate::Slow),
+            "slow-then-very-fast" => Some(GrowthRate::SlowThenVeryFast),
+            "fast-then-very-slow" => Some(GrowthRate::FastThenVerySlow),
+            _ => None,
+        }
+    }
+
+    /// Total experience needed to reach a level, using the games' formulas
+    pub fn experience_for_level(&self, level: u8) -> u32 {
+        let n = level.min(MAX_LEVEL) as i64;
+        if n <= 1 {
+            return 0;
+        }
+        let cube = n * n * n;
+
+        let experience = match self {
+            GrowthRate::Fast => 4 * cube / 5,
+            GrowthRate::Medium => cube,
+            GrowthRate::MediumSlow => 6 * cube / 5 - 15 * n * n + 100 * n - 140,
+            GrowthRate::Slow => 5 * cube / 4,
+            GrowthRate::SlowThenVeryFast => match n {
+                ..=49 => cube * (100 - n) / 50,
+                50..=67 => cube * (150 - n) / 100,
+                68..=97 => cube * ((1911 - 10 * n) / 3) / 500,
+                _ => cube * (160 - n) / 100,
+            },
+            GrowthRate::FastThenVerySlow => match n {
+                ..=14 => cube * ((n + 1) / 3 + 24) / 50,
+                15..=35 => cube * (n + 14) / 50,
+                _ => cube * (n / 2 + 32) / 50,
+            },
+        };
+
+        experience.max(0) as u32
+    }
+
+    /// Highest level whose experience requirement has been met
+    pub fn level_for_experience(&self, experience: u32) -> u8 {
+        (1..=MAX_LEVEL)
+            .rev()
+            .find(|level| self.experience_for_level(*level) <= experience)
+            .unwrap_or(1)
+    }
+}
+
+/// Species-level data shared by every Pokemon of a kind
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Species {
+    pub id: u32,
+    pub name: String,
+    pub flavor_text: Option<String>,
+    /// Pokedex category, e.g. "Seed Pokémon"
+    pub genus: Option<String>,
+    /// 3 (hardest) to 255 (easiest)
+    pub capture_rate: u32,
+    pub base_happiness: Option<u32>,
+    pub growth_rate: GrowthRate,
+    pub habitat: Option<String>,
+    pub egg_groups: Vec<String>,
+    /// Chance of a female, from 0.0 to 1.0; None for genderless species
+    pub female_ratio: Option<f32>,
+    pub is_legendary: bool,
+    pub is_mythical: bool,
+    pub evolves_from: Option<String>,
+    pub evolution_chain_id: Option<u32>,
+}
+
+impl Species {
+    pub fn from_pokeapi_response(response: &PokeApiSpeciesResponse) -> Self {
+        Self {
+            id: response.id,
+            name: response.name.clone(),
+            flavor_text: response.english_flavor_text(),
+            genus: response.english_genus(),
+            capture_rate: response.capture_rate,
+            base_happiness: response.base_happiness,
+            growth_rate: GrowthRate::from_name(&response.growth_rate.name)
+                .unwrap_or(GrowthRate::Medium),
+            habitat: response
+                .habitat
+                .as_ref()
+                .map(|habitat| habitat.name.clone()),
+            egg_groups: response
+                .egg_groups
+                .iter()
+                .map(|group| group.name.clone())
+                .collect(),
+            // PokeAPI gives the female chance in eighths, or -1 for genderless
+            female_ratio: (response.gender_rate >= 0).then(|| response.gender_rate as f32 / 8.0),
+            is_legendary: response.is_legendary,
+            is_mythical: response.is_mythical,
+            evolves_from: response
+                .evolves_from_species
+                .as_ref()
+                .map(|species| species.name.clone()),
+            evolution_chain_id: response
+                .evolution_chain
+                .as_ref()
+                .and_then(|chain| chain.url.split('/').nth_back(1))
+                .and_then(|id| id.parse().ok()),
+        }
+    }
+}
+
+/// A species' whole evolution family, from the base form down every branch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvolutionChain {
+    pub id: u32,
+    pub root: EvolutionNode,
+}
+
+/// One species in an evolution tree. `conditions` are the alternative ways to evolve
+/// into it from its parent (empty for the base form); any one of them is enough.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvolutionNode {
+    pub species_id: u32,
+    pub species_name: String,
+    pub conditions: Vec<EvolutionCondition>,
+    pub evolves_to: Vec<EvolutionNode>,
+}
+
+/// Everything PokeAPI can require for one way of evolving; unset fields don't apply
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvolutionCondition {
+    pub trigger: String,
+    pub min_level: Option<u32>,
+    pub item: Option<String>,
+    pub held_item: Option<String>,
+    pub known_move: Option<String>,
+    pub known_move_type: Option<String>,
+    pub location: Option<String>,
+    pub min_happiness: Option<u32>,
+    pub min_affection: Option<u32>,
+    pub min_beauty: Option<u32>,
+    pub time_of_day: Option<String>,
+    pub gender: Option<u8>,
+    pub party_species: Option<String>,
+    pub party_type: Option<String>,
+    pub trade_species: Option<String>,
+    pub relative_physical_stats: Option<i32>,
+    pub needs_overworld_rain: bool,
+    pub turn_upside_down: bool,
+}
+
+impl EvolutionChain {
+    pub fn from_pokeapi_response(response: &PokeApiEvolutionChainResponse) -> Self {
+        Self {
+            id: response.id,
+            root: EvolutionNode::from_chain_link(&response.chain),
+        }
+    }
+
+    pub fn find(&self, species_name: &str) -> Option<&EvolutionNode> {
+        self.root.find(species_name)
+    }
+
+    /// Species the given species evolves into directly
+    pub fn evolutions_from(&self, species_name: &str) -> Vec<EvolutionNode> {
+        self.find(species_name)
+            .map(|node| node.evolves_to.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl EvolutionNode {
+    fn from_chain_link(link: &PokeApiChainLink) -> Self {
+        // PokeAPI repeats identical details across games, so keep each distinct one once
+        let mut conditions: Vec<EvolutionCondition> = Vec::new();
+        for detail in &link.evolution_details {
+            let condition = EvolutionCondition::from_pokeapi_detail(detail);
+            if !conditions.contains(&condition) {
+                conditions.push(condition);
+            }
+        }
+
+        Self {
+            species_id: link
+                .species
+                .url
+                .split('/')
+                .nth_back(1)
+                .and_then(|id| id.parse().ok())
+                .unwrap_or(0),
+            species_name: link.species.name.clone(),
+            conditions,
+            evolves_to: link.evolves_to.iter().map(Self::from_chain_link).collect(),
+        }
+    }
+
+    fn find(&self, species_name: &str) -> Option<&EvolutionNode> {
+        if self.species_name == species_name {
+            return Some(self);
+        }
+        self.evolves_to
+            .iter()
+            .find_map(|next| next.find(species_name))
+    }
+
+    /// Lowest level any level-up condition asks for
+    pub fn min_level(&self) -> Option<u32> {
+        self.conditions
+            .iter()
+            .filter(|c| c.trigger == "level-up")
+            .filter_map(|c| c.min_level)
+            .min()
+    }
+
+    /// Whether going from one level to another is what makes this evolution possible,
+    /// so evolutions already open at the old level are not announced again. Evolutions
+    /// that also need an item, friendship, a move or a time of day are left to the table.
+    pub fn becomes_available(&self, from_level: u8, to_level: u8) -> bool {
+        self.conditions.iter().any(|c| {
+            c.is_level_only()
+                && c.min_level
+                    .is_some_and(|min| (from_level as u32) < min && min <= to_level as u32)
+        })
+    }
+}
+
+impl EvolutionCondition {
+    fn from_pokeapi_detail(detail: &PokeApiEvolutionDetail) -> Self {
+        let name = |resource: &Option<PokeApiResource>| resource.as_ref().map(|r| r.name.clone());
+
+        Self {
+            trigger: detail.trigger.name.clone(),
+            min_level: detail.min_level,
+            item: name(&detail.item),
+            held_item: name(&detail.held_item),
+            known_move: name(&detail.known_move),
+            known_move_type: name(&detail.known_move_type),
+            location: name(&detail.location),
+            min_happiness: detail.min_happiness,
+            min_affection: detail.min_affection,
+            min_beauty: detail.min_beauty,
+            time_of_day: Some(detail.time_of_day.clone()).filter(|time| !time.is_empty()),
+            gender: detail.gender,
+            party_species: name(&detail.party_species),
+            party_type: name(&detail.party_type),
+            trade_species: name(&detail.trade_species),
+            relative_physical_stats: detail.relative_physical_stats,
+            needs_overworld_rain: detail.needs_overworld_rain,
+            turn_upside_down: detail.turn_upside_down,
+        }
+    }
+
+    // A plain level-up: nothing asked for beyond (optionally) a minimum level
+    fn is_level_only(&self) -> bool {
+        self.trigger == "level-up"
+            && self.item.is_none()
+            && self.held_item.is_none()
+            && self.known_move.is_none()
+            && self.known_move_type.is_none()
+            && self.location.is_none()
+            && self.min_happiness.is_none()
+            && self.min_affection.is_none()
+            && self.min_beauty.is_none()
+            && self.time_of_day.is_none()
+            && self.gender.is_none()
+            && self.party_species.is_none()
+            && self.party_type.is_none()
+            && self.relative_physical_stats.is_none()
+            && !self.needs_overworld_rain
+            && !self.turn_upside_down
+    }
+}
+
+const MAX_IV: u8 = 31;
+const MAX_EV: u8 = 252;
+const MAX_EV_TOTAL: u32 = 510;
+const MAX_CHOSEN_MOVES: usize = 4;
+
+// Natures in game index order. Index / 5 is the raised stat and index % 5 the lowered
+// one, in the order attack, defense, speed, special attack, special defense; the five
+// natures where the two coincide are neutral.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Nature {
+    #[default]
+    Hardy,
+    Lonely,
+    Brave,
+    Adamant,
+    Naughty,
+    Bold,
+    Docile,
+    Relaxed,
+    Impish,
+    Lax,
+    Timid,
+    Hasty,
+    Serious,
+    Jolly,
+    Naive,
+    Modest,
+    Mild,
+    Quiet,
+    Bashful,
+    Rash,
+    Calm,
+    Gentle,
+    Sassy,
+    Careful,
+    Quirky,
+}
+
+impl Nature {
+    /// Multipliers for attack, defense, speed, special attack and special defense
+    pub fn stat_multipliers(&self) -> [f32; 5] {
+        let index = *self as usize;
+        let (raised, lowered) = (index / 5, index % 5);
+
+        let mut multipliers = [1.0; 5];
+        if raised != lowered {
+            multipliers[raised] = 1.1;
+            multipliers[lowered] = 0.9;
+        }
+        multipliers
+    }
+}
+
+/// Individual values (0-31 each) or effort values (0-252 each, 510 in total)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatSpread {
+    pub hp: u8,
+    pub attack: u8,
+    pub defense: u8,
+    pub special_attack: u8,
+    pub special_defense: u8,
+    pub speed: u8,
+}
+
+impl StatSpread {
+    fn values(&self) -> [u8; 6] {
+        [
+            self.hp,
+            self.attack,
+            self.defense,
+            self.special_attack,
+            self.special_defense,
+            self.speed,
+        ]
+    }
+}
+
+/// What sets one caught Pokemon apart from the rest of its species
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PokemonCustomization {
+    pub nature: Nature,
+    pub ivs: StatSpread,
+    pub evs: StatSpread,
+    pub held_item: Option<String>,
+    pub ability: Option<String>,
+    /// Up to four move names from the species' learnset; empty means the latest learned
+    pub moves: Vec<String>,
+}
+
+impl PokemonCustomization {
+    /// Check the choices are legal for this species at this level
+    pub fn validate(&self, species: &Pokemon, level: u8) -> Result<(), String> {
+        if let Some(iv) = self.ivs.values().iter().find(|iv| **iv > MAX_IV) {
+            return Err(format!("IVs range from 0 to {}, got {}", MAX_IV, iv));
+        }
+        if let Some(ev) = self.evs.values().iter().find(|ev| **ev > MAX_EV) {
+            return Err(format!("EVs range from 0 to {}, got {}", MAX_EV, ev));
+        }
+        let ev_total: u32 = self.evs.values().iter().map(|ev| *ev as u32).sum();
+        if ev_total > MAX_EV_TOTAL {
+            return Err(format!(
+                "EVs may total at most {}, got {}",
+                MAX_EV_TOTAL, ev_total
+            ));
+        }
+
+        if let Some(ability) = &self.ability {
+            if !species.abilities.iter().any(|a| &a.name == ability) {
+                return Err(format!(
+                    "{} cannot have the ability {}",
+                    species.name, ability
+                ));
+            }
+        }
+
+        if self.moves.len() > MAX_CHOSEN_MOVES {
+            return Err(format!(
+                "A Pokemon can know at most {} moves",
+                MAX_CHOSEN_MOVES
+            ));
+        }
+        for (index, name) in self.moves.iter().enumerate() {
+            if self.moves[..index].contains(name) {
+                return Err(format!("{} is chosen more than once", name));
+            }
+            match species.moves.iter().find(|m| &m.name == name) {
+                Some(m) if m.level_learned_at <= level as u32 => {}
+                Some(m) => {
+                    return Err(format!(
+                        "{} is not learned until level {}",
+                        name, m.level_learned_at
+                    ))
+                }
+                None => return Err(format!("{} cannot learn {}", species.name, name)),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The species with this individual's stats and moves. Base stats become the
+    /// equivalent base for the game's stat formula, (2 * base + IV + EV / 4), then the
+    /// nature raises or lowers one stat by 10%.
+    pub fn apply_to(&self, species: &Pokemon) -> Pokemon {
+        let [attack, defense, speed, special_attack, special_defense] =
+            self.nature.stat_multipliers();
+        let adjust = |base: u32, iv: u8, ev: u8, multiplier: f32| {
+            let equivalent = base as f32 + (iv as f32 + (ev / 4) as f32) / 2.0;
+            (equivalent * multiplier).round() as u32
+        };
+        let (base, ivs, evs) = (&species.base_stats, &self.ivs, &self.evs);
+
+        let mut individual = species.clone();
+        individual.base_stats = BaseStats {
+            hp: adjust(base.hp, ivs.hp, evs.hp, 1.0),
+            attack: adjust(base.attack, ivs.attack, evs.attack, attack),
+            defense: adjust(base.defense, ivs.defense, evs.defense, defense),
+            special_attack: adjust(
+                base.special_attack,
+                ivs.special_attack,
+                evs.special_attack,
+                special_attack,
+            ),
+            special_defense: adjust(
+                base.special_defense,
+                ivs.special_defense,
+                evs.special_defense,
+                special_defense,
+            ),
+            speed: adjust(base.speed, ivs.speed, evs.speed, speed),
+        };
+
+        if !self.moves.is_empty() {
+            individual.moves = self
+                .moves
+                .iter()
+                .filter_map(|name| species.moves.iter().find(|m| &m.name == name).cloned())
+                .collect();
+        }
+
+        individual
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::bulbasaur;
+
+    #[test]
+    fn generations_follow_national_dex_ranges() {
+        assert_eq!(generation_for_national_dex_id(1), Some(1));
+        assert_eq!(generation_for_national_dex_id(151), Some(1));
+        assert_eq!(generation_for_national_dex_id(152), Some(2));
+        assert_eq!(generation_for_national_dex_id(1025), Some(9));
+        assert_eq!(generation_for_national_dex_id(10001), None);
+    }
+
+    #[test]
+    fn natures_raise_one_stat_and_lower_another() {
+        assert_eq!(Nature::Hardy.stat_multipliers(), [1.0; 5]);
+        assert_eq!(Nature::Quirky.stat_multipliers(), [1.0; 5]);
+        assert_eq!(
+            Nature::Adamant.stat_multipliers(),
+            [1.1, 1.0, 1.0, 0.9, 1.0]
+        );
+        assert_eq!(Nature::Modest.stat_multipliers(), [0.9, 1.0, 1.0, 1.1, 1.0]);
+        assert_eq!(Nature::Timid.stat_multipliers(), [0.9, 1.0, 1.1, 1.0, 1.0]);
+        assert_eq!(Nature::Calm.stat_multipliers(), [0.9, 1.0, 1.0, 1.0, 1.1]);
+    }
+
+    #[test]
+    fn customization_rejects_illegal_choices() {
+        let species = bulbasaur();
+        let legal = PokemonCustomization {
+            ivs: StatSpread {
+                attack: 31,
+                ..Default::default()
+            },
+            evs: StatSpread {
+                attack: 252,
+                speed: 252,
+                hp: 6,
+                ..Default::default()
+            },
+            ability: Some("chlorophyll".to_string()),
+            moves: vec!["tackle".to_string(), "vine-whip".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(legal.validate(&species, 5), Ok(()));
+
+        let mut invalid = legal.clone();
+        invalid.ivs.hp = 32;
+        assert!(invalid.validate(&species, 5).is_err());
+
+        let mut invalid = legal.clone();
+        invalid.evs.hp = 7;
+        assert!(invalid.validate(&species, 5).is_err());
+
+        let mut invalid = legal.clone();
+        invalid.ability = Some("blaze".to_string());
+        assert!(invalid.validate(&species, 5).is_err());
+
+        let mut invalid = legal.clone();
+        invalid.moves.push("razor-leaf".to_string());
+        assert!(invalid.validate(&species, 5).is_err());
+        assert!(invalid.validate(&species, 20).is_ok());
+
+        let mut invalid = legal.clone();
+        invalid.moves.push("tackle".to_string());
+        assert!(invalid.validate(&species, 5).is_err());
+
+        let mut invalid = legal;
+        invalid.moves = vec!["surf".to_string()];
+        assert!(invalid.validate(&species, 100).is_err());
+    }
+
+    #[test]
+    fn customization_adjusts_stats_and_moves() {
+        let species = bulbasaur();
+        let customization = PokemonCustomization {
+            nature: Nature::Modest,
+            ivs: StatSpread {
+                hp: 31,
+                special_attack: 31,
+                ..Default::default()
+            },
+            evs: StatSpread {
+                special_attack: 252,
+                ..Default::default()
+            },
+            moves: vec!["vine-whip".to_string()],
+            ..Default::default()
+        };
+        let individual = customization.apply_to(&species);
+
+        // 45 + 31 / 2
+        assert_eq!(individual.base_stats.hp, 61);
+        // (65 + (31 + 63) / 2) * 1.1
+        assert_eq!(individual.base_stats.special_attack, 123);
+        // 49 * 0.9
+        assert_eq!(individual.base_stats.attack, 44);
+        assert_eq!(individual.base_stats.defense, 49);
+        assert_eq!(individual.moves.len(), 1);
+        assert_eq!(individual.moves[0].name, "vine-whip");
+    }
+
+    #[test]
+    fn growth_rates_match_the_games_at_level_100() {
+        let expected = [
+            (GrowthRate::Fast, 800_000),
+            (GrowthRate::Medium, 1_000_000),
+            (GrowthRate::MediumSlow, 1_059_860),
+            (GrowthRate::Slow, 1_250_000),
+            (GrowthRate::SlowThenVeryFast, 600_000),
+            (GrowthRate::FastThenVerySlow, 1_640_000),
+        ];
+        for (rate, experience) in expected {
+            assert_eq!(rate.experience_for_level(1), 0, "{:?}", rate);
+            assert_eq!(rate.experience_for_level(100), experience, "{:?}", rate);
+            assert_eq!(rate.experience_for_level(120), experience, "{:?}", rate);
+        }
+    }
+
+    #[test]
+    fn growth_rates_never_decrease() {
+        for name in [
+            "fast",
+            "medium",
+            "medium-slow",
+            "slow",
+            "slow-then-very-fast",
+            "fast-then-very-slow",
+        ] {
+            let rate = GrowthRate::from_name(name).unwrap();
+            for level in 2..=MAX_LEVEL {
+                assert!(
+                    rate.experience_for_level(level) > rate.experience_for_level(level - 1),
+                    "{} at level {}",
+                    name,
+                    level
+                );
+            }
+        }
+        assert_eq!(GrowthRate::from_name("erratic"), None);
+    }
+
+    #[test]
+    fn level_for_experience_inverts_the_curve() {
+        let rate = GrowthRate::MediumSlow;
+        assert_eq!(rate.level_for_experience(0), 1);
+        for level in 2..=MAX_LEVEL {
+            let floor = rate.experience_for_level(level);
+            assert_eq!(rate.level_for_experience(floor), level);
+            assert_eq!(rate.level_for_experience(floor - 1), level - 1);
+        }
+        assert_eq!(rate.level_for_experience(u32::MAX), MAX_LEVEL);
+    }
+
+    #[test]
+    fn evolution_chains_keep_every_branch_and_condition() {
+        let link = |name: &str,
+                    id: u32,
+                    details: serde_json::Value,
+                    evolves_to: Vec<serde_json::Value>| {
+            serde_json::json!({
+                "species": {
+                    "name": name,
+                    "url": format!("https://pokeapi.co/api/v2/pokemon-species/{}/", id),
+                },
+                "evolution_details": details,
+                "evolves_to": evolves_to,
+            })
+        };
+        let stone = |item: &str| {
+            serde_json::json!([{
+                "trigger": { "name": "use-item", "url": "" },
+                "item": { "name": item, "url": "" },
+            }])
+        };
+        let response: PokeApiEvolutionChainResponse = serde_json::from_value(serde_json::json!({
+            "id": 67,
+            "chain": link("eevee", 133, serde_json::json!([]), vec![
+                link("vaporeon", 134, stone("water-stone"), vec![]),
+                link("jolteon", 135, stone("thunder-stone"), vec![]),
+            ]),
+        }))
+        .unwrap();
+
+        let chain = EvolutionChain::from_pokeapi_response(&response);
+        assert_eq!(chain.root.species_id, 133);
+        let evolutions = chain.evolutions_from("eevee");
+        assert_eq!(evolutions.len(), 2);
+        assert_eq!(evolutions[1].species_name, "jolteon");
+        assert_eq!(
+            evolutions[1].conditions[0].item.as_deref(),
+            Some("thunder-stone")
+        );
+        assert!(chain.evolutions_from("jolteon").is_empty());
+        assert!(chain.find("pikachu").is_none());
+    }
+
+    fn level_up(min_level: Option<u32>) -> EvolutionCondition {
+        EvolutionCondition {
+            trigger: "level-up".to_string(),
+            min_level,
+            item: None,
+            held_item: None,
+            known_move: None,
+            known_move_type: None,
+            location: None,
+            min_happiness: None,
+            min_affection: None,
+            min_beauty: None,
+            time_of_day: None,
+            gender: None,
+            party_species: None,
+            party_type: None,
+            trade_species: None,
+            relative_physical_stats: None,
+            needs_overworld_rain: false,
+            turn_upside_down: false,
+        }
+    }
+
+    fn evolution(conditions: Vec<EvolutionCondition>) -> EvolutionNode {
+        EvolutionNode {
+            species_id: 2,
+            species_name: "ivysaur".to_string(),
+            conditions,
+            evolves_to: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn evolutions_are_reported_once_when_their_level_is_reached() {
+        let node = evolution(vec![level_up(Some(16))]);
+        assert!(node.becomes_available(15, 16));
+        assert!(node.becomes_available(5, 30));
+        assert!(!node.becomes_available(16, 17));
+        assert!(!node.becomes_available(10, 15));
+    }
+
+    #[test]
+    fn evolutions_needing_more_than_a_level_are_not_reported() {
+        let mut friendship = level_up(None);
+        friendship.min_happiness = Some(160);
+        assert!(!evolution(vec![friendship]).becomes_available(1, 100));
+
+        let mut stone = level_up(Some(20));
+        stone.trigger = "use-item".to_string();
+        assert!(!evolution(vec![stone]).becomes_available(1, 100));
+    }
+
+    #[test]
+    fn generations_and_pokedexes_from_pokeapi() {
+        let species = |name: &str, id: u32| {
+            serde_json::json!({
+                "name": name,
+                "url": format!("https://pokeapi.co/api/v2/pokemon-species/{}/", id),
+            })
+        };
+        let generation: PokeApiGenerationResponse = serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "name": "generation-i",
+            "main_region": { "name": "kanto", "url": "" },
+            "pokemon_species": [species("pikachu", 25), species("bulbasaur", 1)],
+        }))
+        .unwrap();
+        let generation = Generation::from_pokeapi_response(&generation);
+        assert_eq!(generation.main_region, "kanto");
+        assert_eq!(generation.species_ids, vec![1, 25]);
+
+        let pokedex: PokeApiPokedexResponse = serde_json::from_value(serde_json::json!({
+            "id": 2,
+            "name": "kanto",
+            "is_main_series": true,
+            "region": null,
+            "pokemon_entries": [
+                { "entry_number": 25, "pokemon_species": species("pikachu", 25) },
+                { "entry_number": 26, "pokemon_species": { "name": "broken", "url": "" } },
+            ],
+        }))
+        .unwrap();
+        let (pokedex, entries) = Pokedex::from_pokeapi_response(&pokedex);
+        assert_eq!(pokedex.region, None);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].species_id, 25);
+    }
+
+    #[test]
+    fn resource_ids_come_from_the_url() {
+        let resource = PokeApiResource {
+            name: "bulbasaur".to_string(),
+            url: "https://pokeapi.co/api/v2/pokemon-species/1/".to_string(),
+        };
+        assert_eq!(resource.id(), Some(1));
+        let broken = PokeApiResource {
+            name: "missingno".to_string(),
+            url: String::new(),
+        };
+        assert_eq!(broken.id(), None);
+    }
+}
@@ -0,0 +1,404 @@
+use reqwest::header::RETRY_AFTER;
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+pub const POKEAPI_BASE_URL: &str = "https://pokeapi.co/api/v2";
+
+// Environment variables read at startup to pick a source
+const MIRROR_URL_VAR: &str = "POKEAPI_MIRROR_URL";
+const FIXTURE_FILE_VAR: &str = "POKEMON_FIXTURE_FILE";
+const API_DATA_DIR_VAR: &str = "POKEAPI_DATA_DIR";
+
+pub type SourceFuture<'a> = Pin<Box<dyn Future<Output = Result<String, SourceError>> + Send + 'a>>;
+
+/// A fetch failed. Rate limits and unavailable sources are worth retrying; the rest are not.
+#[derive(Debug)]
+pub enum SourceError {
+    NotFound(String),
+    RateLimited {
+        message: String,
+        retry_after: Option<Duration>,
+    },
+    Unavailable(String),
+    Invalid(String),
+}
+
+impl SourceError {
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            SourceError::RateLimited { .. } | SourceError::Unavailable(_)
+        )
+    }
+
+    /// How long the source asked us to wait, if it said
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            SourceError::RateLimited { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for SourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SourceError::NotFound(message)
+            | SourceError::RateLimited { message, .. }
+            | SourceError::Unavailable(message)
+            | SourceError::Invalid(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for SourceError {}
+
+/// Where Pokemon data comes from. Paths are PokeAPI resource paths relative to
+/// `/api/v2`, such as `pokemon/25`, `move/tackle` or `pokemon?limit=20&offset=40`.
+pub trait PokemonSource: Send + Sync {
+    /// Human-readable name for logs and error messages
+    fn describe(&self) -> String;
+
+    /// Raw JSON body of a resource
+    fn fetch<'a>(&'a self, path: &'a str) -> SourceFuture<'a>;
+}
+
+impl<'s> dyn PokemonSource + 's {
+    /// Fetch a resource and deserialize it
+    pub async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, SourceError> {
+        let body = self.fetch(path).await?;
+        serde_json::from_str(&body)
+            .map_err(|e| SourceError::Invalid(format!("Failed to parse {}: {}", path, e)))
+    }
+}
+
+/// The source picked at startup, shared as Tauri state
+pub type SharedSource = Arc<dyn PokemonSource>;
+
+/// Which source to use, chosen from the environment at startup
+#[derive(Debug, Clone)]
+pub enum SourceConfig {
+    Live,
+    Mirror(String),
+    ApiData(PathBuf),
+    Fixture(PathBuf),
+}
+
+impl SourceConfig {
+    /// `POKEMON_FIXTURE_FILE` wins, then `POKEAPI_DATA_DIR`, then `POKEAPI_MIRROR_URL`;
+    /// with none set, use pokeapi.co
+    pub fn from_env() -> Self {
+        if let Some(path) = std::env::var_os(FIXTURE_FILE_VAR).filter(|v| !v.is_empty()) {
+            return SourceConfig::Fixture(PathBuf::from(path));
+        }
+        if let Some(path) = std::env::var_os(API_DATA_DIR_VAR).filter(|v| !v.is_empty()) {
+            return SourceConfig::ApiData(PathBuf::from(path));
+        }
+        match std::env::var(MIRROR_URL_VAR) {
+            Ok(url) if !url.trim().is_empty() => SourceConfig::Mirror(url),
+            _ => SourceConfig::Live,
+        }
+    }
+
+    pub fn build(&self) -> Result<SharedSource, String> {
+        Ok(match self {
+            SourceConfig::Live => Arc::new(HttpSource::pokeapi()),
+            SourceConfig::Mirror(url) => Arc::new(HttpSource::mirror(url)),
+            SourceConfig::ApiData(path) => Arc::new(ApiDataSource::open(path)?),
+            SourceConfig::Fixture(path) => Arc::new(FixtureSource::from_file(path)?),
+        })
+    }
+}
+
+/// PokeAPI over HTTP, either the public instance or a self-hosted mirror
+pub struct HttpSource {
+    client: reqwest::Client,
+    base_url: String,
+    is_mirror: bool,
+}
+
+impl HttpSource {
+    pub fn pokeapi() -> Self {
+        HttpSource {
+            client: reqwest::Client::new(),
+            base_url: POKEAPI_BASE_URL.to_string(),
+            is_mirror: false,
+        }
+    }
+
+    /// A mirror's base URL, including the `/api/v2` part
+    pub fn mirror(base_url: &str) -> Self {
+        HttpSource {
+            client: reqwest::Client::new(),
+            base_url: base_url.trim().trim_end_matches('/').to_string(),
+            is_mirror: true,
+        }
+    }
+}
+
+impl PokemonSource for HttpSource {
+    fn describe(&self) -> String {
+        if self.is_mirror {
+            format!("PokeAPI mirror at {}", self.base_url)
+        } else {
+            "PokeAPI".to_string()
+        }
+    }
+
+    fn fetch<'a>(&'a self, path: &'a str) -> SourceFuture<'a> {
+        Box::pin(async move {
+            let url = format!("{}/{}", self.base_url, path.trim_start_matches('/'));
+
+            let response =
+                self.client.get(&url).send().await.map_err(|e| {
+                    SourceError::Unavailable(format!("Failed to fetch {}: {}", url, e))
+                })?;
+
+            let status = response.status();
+            if !status.is_success() {
+                let message = format!("{} returned status: {}", self.describe(), status);
+                return Err(match status {
+                    StatusCode::NOT_FOUND => SourceError::NotFound(message),
+                    StatusCode::TOO_MANY_REQUESTS => SourceError::RateLimited {
+                        message,
+                        retry_after: response
+                            .headers()
+                            .get(RETRY_AFTER)
+                            .and_then(|value| value.to_str().ok()?.trim().parse().ok())
+                            .map(Duration::from_secs),
+                    },
+                    _ if status.is_server_error() => SourceError::Unavailable(message),
+                    _ => SourceError::Invalid(message),
+                });
+            }
+
+            response
+                .text()
+                .await
+                .map_err(|e| SourceError::Unavailable(format!("Failed to read {}: {}", url, e)))
+        })
+    }
+}
+
+/// A local copy of the JSON tree published by PokeAPI's `api-data` project, where
+/// `pokemon/25` lives at `pokemon/25/index.json` under the `api/v2` directory.
+/// Clones share the name indexes, so files can be read on a blocking thread.
+#[derive(Clone)]
+pub struct ApiDataSource {
+    root: PathBuf,
+    // Resource kind to its name-to-id index, read from the kind's index.json on first use
+    names: Arc<Mutex<HashMap<String, HashMap<String, u32>>>>,
+}
+
+impl ApiDataSource {
+    /// Accepts either a checkout of the repository or its `data/api/v2` directory
+    pub fn open(dir: &Path) -> Result<Self, String> {
+        let root = [dir.join("data").join("api").join("v2"), dir.to_path_buf()]
+            .into_iter()
+            .find(|root| root.join("pokemon").join("index.json").is_file())
+            .ok_or_else(|| format!("{} is not a PokeAPI api-data directory", dir.display()))?;
+
+        Ok(ApiDataSource {
+            root,
+            names: Arc::new(Mutex::new(HashMap::new())),
+        })
+    }
+
+    fn read(&self, relative: &Path) -> Result<String, SourceError> {
+        let file = self.root.join(relative).join("index.json");
+        std::fs::read_to_string(&file).map_err(|e| {
+            let message = format!("Failed to read {}: {}", file.display(), e);
+            match e.kind() {
+                std::io::ErrorKind::NotFound => SourceError::NotFound(message),
+                _ => SourceError::Invalid(message),
+            }
+        })
+    }
+
+    fn read_list(&self, kind: &str) -> Result<Vec<Value>, SourceError> {
+        let list: Value = serde_json::from_str(&self.read(Path::new(kind))?)
+            .map_err(|e| SourceError::Invalid(format!("Failed to parse {} list: {}", kind, e)))?;
+        Ok(list["results"].as_array().cloned().unwrap_or_default())
+    }
+
+    // The dump only has directories by id, so names go through the kind's list
+    fn id_for_name(&self, kind: &str, name: &str) -> Result<u32, SourceError> {
+        let mut names = self.names.lock().unwrap_or_else(|e| e.into_inner());
+        if !names.contains_key(kind) {
+            let index = self
+                .read_list(kind)?
+                .iter()
+                .filter_map(|resource| {
+                    let id = resource["url"]
+                        .as_str()?
+                        .split('/')
+                        .nth_back(1)?
+                        .parse()
+                        .ok()?;
+                    Some((resource["name"].as_str()?.to_string(), id))
+                })
+                .collect();
+            names.insert(kind.to_string(), index);
+        }
+
+        names[kind].get(name).copied().ok_or_else(|| {
+            SourceError::NotFound(format!(
+                "{} has no {} named {}",
+                self.describe(),
+                kind,
+                name
+            ))
+        })
+    }
+
+    fn load(&self, path: &str) -> Result<String, SourceError> {
+        let path = path.trim_matches('/');
+        let (resource, query) = path.split_once('?').unwrap_or((path, ""));
+
+        match resource.trim_end_matches('/').split_once('/') {
+            Some((kind, key)) => {
+                let id = match key.parse::<u32>() {
+                    Ok(id) => id,
+                    Err(_) => self.id_for_name(kind, key)?,
+                };
+                self.read(&Path::new(kind).join(id.to_string()))
+            }
+            None => {
+                // The dump's lists hold every resource; page them like the live API
+                Ok(list_page(self.read_list(resource)?, query))
+            }
+        }
+    }
+}
+
+impl PokemonSource for ApiDataSource {
+    fn describe(&self) -> String {
+        format!("PokeAPI data at {}", self.root.display())
+    }
+
+    fn fetch<'a>(&'a self, path: &'a str) -> SourceFuture<'a> {
+        let data = self.clone();
+        let path = path.to_string();
+        Box::pin(async move {
+            tokio::task::spawn_blocking(move || data.load(&path))
+                .await
+                .map_err(|e| SourceError::Unavailable(format!("Failed to read api-data: {}", e)))?
+        })
+    }
+}
+
+/// Canned resources held in memory, for tests and running without a network.
+/// Resources are found by id or name; list endpoints are built from what is loaded.
+#[derive(Default)]
+pub struct FixtureSource {
+    // "pokemon/25" and "pokemon/pikachu" both map to the same body
+    resources: HashMap<String, String>,
+    // Resource kind to its loaded ids and names, for list endpoints
+    listings: HashMap<String, BTreeMap<u32, String>>,
+}
+
+impl FixtureSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load a JSON file mapping each resource kind to an array of resources, e.g.
+    /// `{ "pokemon": [...], "pokemon-species": [...], "move": [...] }`
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read fixtures {}: {}", path.display(), e))?;
+        let kinds: HashMap<String, Vec<Value>> = serde_json::from_str(&text)
+            .map_err(|e| format!("Failed to parse fixtures {}: {}", path.display(), e))?;
+
+        let mut source = Self::new();
+        for (kind, resources) in kinds {
+            for resource in resources {
+                source.add_resource(&kind, resource)?;
+            }
+        }
+        Ok(source)
+    }
+
+    /// Add a resource under its kind, reachable by its `id` and `name` fields
+    pub fn add_resource(&mut self, kind: &str, resource: Value) -> Result<(), String> {
+        let id = resource["id"]
+            .as_u64()
+            .ok_or_else(|| format!("Fixture {} resource has no id", kind))? as u32;
+        let name = resource["name"].as_str().map(str::to_string);
+        let body = resource.to_string();
+
+        if let Some(name) = &name {
+            self.resources
+                .insert(format!("{}/{}", kind, name), body.clone());
+        }
+        self.resources.insert(format!("{}/{}", kind, id), body);
+        self.listings
+            .entry(kind.to_string())
+            .or_default()
+            .insert(id, name.unwrap_or_else(|| id.to_string()));
+        Ok(())
+    }
+
+    // Named-resource list built from the loaded resources
+    fn list(&self, kind: &str, query: &str) -> Option<String> {
+        let results = self
+            .listings
+            .get(kind)?
+            .iter()
+            .map(|(id, name)| json!({ "name": name, "url": format!("fixture://{}/{}/", kind, id) }))
+            .collect();
+        Some(list_page(results, query))
+    }
+}
+
+impl PokemonSource for FixtureSource {
+    fn describe(&self) -> String {
+        "fixture data".to_string()
+    }
+
+    fn fetch<'a>(&'a self, path: &'a str) -> SourceFuture<'a> {
+        let path = path.trim_matches('/');
+        let (resource, query) = path.split_once('?').unwrap_or((path, ""));
+        let resource = resource.trim_end_matches('/');
+
+        let body = self
+            .resources
+            .get(path)
+            .or_else(|| self.resources.get(resource))
+            .cloned()
+            .or_else(|| self.list(resource, query))
+            .ok_or_else(|| {
+                SourceError::NotFound(format!("{} has no resource {}", self.describe(), path))
+            });
+        Box::pin(std::future::ready(body))
+    }
+}
+
+// One page of a named-resource list in PokeAPI's shape, by the query's limit and offset
+fn list_page(results: Vec<Value>, query: &str) -> String {
+    let mut limit = 20;
+    let mut offset = 0;
+    for (key, value) in query.split('&').filter_map(|pair| pair.split_once('=')) {
+        match key {
+            "limit" => limit = value.parse().unwrap_or(limit),
+            "offset" => offset = value.parse().unwrap_or(offset),
+            _ => {}
+        }
+    }
+
+    json!({
+        "count": results.len(),
+        "next": null,
+        "previous": null,
+        "results": results.into_iter().skip(offset).take(limit).collect::<Vec<_>>(),
+    })
+    .to_string()
+}
@@ -0,0 +1,294 @@
+//! Sample Pokemon and databases shared by the unit tests
+
+use crate::database::Database;
+use crate::pokemon::{Ability, BaseStats, Move, Pokemon, PokemonType};
+use crate::source::FixtureSource;
+use serde_json::{json, Value};
+use std::ops::Deref;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+pub fn level_up_move(
+    name: &str,
+    move_type: &str,
+    damage_class: &str,
+    power: Option<u32>,
+    level: u32,
+) -> Move {
+    Move {
+        name: name.to_string(),
+        power,
+        accuracy: Some(100),
+        pp: 15,
+        move_type: move_type.to_string(),
+        damage_class: damage_class.to_string(),
+        priority: 0,
+        target: "selected-pokemon".to_string(),
+        effect: None,
+        effect_chance: None,
+        level_learned_at: level,
+    }
+}
+
+pub fn snorlax() -> Pokemon {
+    let mut pokemon = Pokemon::new(143, "snorlax".to_string());
+    pokemon.height = 21;
+    pokemon.weight = 4600;
+    pokemon.base_stats = BaseStats {
+        hp: 160,
+        attack: 110,
+        defense: 65,
+        special_attack: 65,
+        special_defense: 110,
+        speed: 30,
+    };
+    pokemon.types = vec![PokemonType {
+        name: "normal".to_string(),
+        slot: 1,
+    }];
+    pokemon.moves = vec![level_up_move(
+        "body-slam",
+        "normal",
+        "physical",
+        Some(85),
+        1,
+    )];
+    pokemon
+}
+
+pub fn bulbasaur() -> Pokemon {
+    let mut pokemon = Pokemon::new(1, "bulbasaur".to_string());
+    pokemon.height = 7;
+    pokemon.weight = 69;
+    pokemon.base_stats = BaseStats {
+        hp: 45,
+        attack: 49,
+        defense: 49,
+        special_attack: 65,
+        special_defense: 65,
+        speed: 45,
+    };
+    pokemon.types = ["grass", "poison"]
+        .iter()
+        .zip(1..)
+        .map(|(name, slot)| PokemonType {
+            name: name.to_string(),
+            slot,
+        })
+        .collect();
+    pokemon.abilities = vec![
+        Ability {
+            name: "overgrow".to_string(),
+            is_hidden: false,
+            slot: 1,
+        },
+        Ability {
+            name: "chlorophyll".to_string(),
+            is_hidden: true,
+            slot: 3,
+        },
+    ];
+    pokemon.moves = [
+        ("tackle", "normal", 1),
+        ("vine-whip", "grass", 3),
+        ("razor-leaf", "grass", 20),
+    ]
+    .iter()
+    .map(|(name, move_type, level)| level_up_move(name, move_type, "physical", Some(40), *level))
+    .collect();
+    pokemon
+}
+
+static NEXT_DATABASE: AtomicU32 = AtomicU32::new(0);
+
+// A database file of its own in the temp directory, removed on drop
+pub struct TempDatabase {
+    database: Database,
+    path: PathBuf,
+}
+
+impl TempDatabase {
+    pub async fn new() -> Self {
+        let path = std::env::temp_dir().join(format!(
+            "pokedex-test-{}-{}.db",
+            std::process::id(),
+            NEXT_DATABASE.fetch_add(1, Ordering::Relaxed)
+        ));
+        let database = Database::open(&path).await.unwrap();
+        TempDatabase { database, path }
+    }
+
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+}
+
+impl Deref for TempDatabase {
+    type Target = Database;
+
+    fn deref(&self) -> &Database {
+        &self.database
+    }
+}
+
+impl Drop for TempDatabase {
+    fn drop(&mut self) {
+        for suffix in ["", "-wal", "-shm"] {
+            let mut file = self.path.clone().into_os_string();
+            file.push(suffix);
+            let _ = std::fs::remove_file(file);
+        }
+    }
+}
+
+// PokeAPI resources for Pikachu and Kanto, in the shape the API serves them
+
+pub fn resource(kind: &str, name: &str, id: u32) -> Value {
+    json!({ "name": name, "url": format!("https://pokeapi.co/api/v2/{}/{}/", kind, id) })
+}
+
+pub fn pikachu() -> Value {
+    let stat = |name: &str, base_stat: u32| json!({ "base_stat": base_stat, "effort": 0, "stat": resource("stat", name, 1) });
+    json!({
+        "id": 25,
+        "name": "pikachu",
+        "height": 4,
+        "weight": 60,
+        "stats": [
+            stat("hp", 35),
+            stat("attack", 55),
+            stat("defense", 40),
+            stat("special-attack", 50),
+            stat("special-defense", 50),
+            stat("speed", 90),
+        ],
+        "types": [{ "slot": 1, "type": resource("type", "electric", 13) }],
+        "moves": [{
+            "move": resource("move", "thunder-shock", 84),
+            "version_group_details": [{
+                "level_learned_at": 1,
+                "move_learn_method": resource("move-learn-method", "level-up", 1),
+                "version_group": resource("version-group", "scarlet-violet", 25),
+            }],
+        }],
+        "abilities": [{ "ability": resource("ability", "static", 9), "is_hidden": false, "slot": 1 }],
+        "sprites": {
+            "front_default": null,
+            "front_shiny": null,
+            "back_default": null,
+            "back_shiny": null,
+            "other": null,
+        },
+        "species": resource("pokemon-species", "pikachu", 25),
+    })
+}
+
+pub fn pikachu_species() -> Value {
+    json!({
+        "id": 25,
+        "name": "pikachu",
+        "flavor_text_entries": [{
+            "flavor_text": "It stores electricity in its cheeks.",
+            "language": resource("language", "en", 9),
+            "version": resource("version", "red", 1),
+        }],
+        "genera": [{ "genus": "Mouse Pokémon", "language": resource("language", "en", 9) }],
+        "capture_rate": 190,
+        "base_happiness": 50,
+        "growth_rate": resource("growth-rate", "medium", 2),
+        "habitat": resource("pokemon-habitat", "forest", 2),
+        "egg_groups": [resource("egg-group", "ground", 5)],
+        "gender_rate": 4,
+        "is_legendary": false,
+        "is_mythical": false,
+        "evolves_from_species": resource("pokemon-species", "pichu", 172),
+        "evolution_chain": { "url": "https://pokeapi.co/api/v2/evolution-chain/10/" },
+    })
+}
+
+pub fn thunder_shock() -> Value {
+    json!({
+        "id": 84,
+        "name": "thunder-shock",
+        "accuracy": 100,
+        "power": 40,
+        "pp": 30,
+        "priority": 0,
+        "type": resource("type", "electric", 13),
+        "damage_class": resource("move-damage-class", "special", 3),
+        "target": resource("move-target", "selected-pokemon", 10),
+        "effect_chance": 10,
+        "effect_entries": [],
+    })
+}
+
+pub fn pikachu_evolution_chain() -> Value {
+    json!({
+        "id": 10,
+        "chain": {
+            "species": resource("pokemon-species", "pichu", 172),
+            "evolution_details": [],
+            "evolves_to": [{
+                "species": resource("pokemon-species", "pikachu", 25),
+                "evolution_details": [
+                    { "trigger": resource("evolution-trigger", "level-up", 1), "min_happiness": 220 },
+                ],
+                "evolves_to": [{
+                    "species": resource("pokemon-species", "raichu", 26),
+                    "evolution_details": [{
+                        "trigger": resource("evolution-trigger", "use-item", 3),
+                        "item": resource("item", "thunder-stone", 83),
+                    }],
+                    "evolves_to": [],
+                }],
+            }],
+        },
+    })
+}
+
+pub fn kanto_generation_and_pokedex() -> Vec<(&'static str, Value)> {
+    vec![
+        (
+            "generation",
+            json!({
+                "id": 1,
+                "name": "generation-i",
+                "main_region": resource("region", "kanto", 1),
+                "pokemon_species": [
+                    resource("pokemon-species", "pikachu", 25),
+                    resource("pokemon-species", "bulbasaur", 1),
+                ],
+            }),
+        ),
+        (
+            "pokedex",
+            json!({
+                "id": 2,
+                "name": "kanto",
+                "is_main_series": true,
+                "region": resource("region", "kanto", 1),
+                "pokemon_entries": [
+                    { "entry_number": 25, "pokemon_species": resource("pokemon-species", "pikachu", 25) },
+                    { "entry_number": 1, "pokemon_species": resource("pokemon-species", "bulbasaur", 1) },
+                ],
+            }),
+        ),
+    ]
+}
+
+pub fn fixture_source(resources: Vec<(&str, Value)>) -> FixtureSource {
+    let mut source = FixtureSource::new();
+    for (kind, resource) in resources {
+        source.add_resource(kind, resource).unwrap();
+    }
+    source
+}
+
+pub fn pikachu_source() -> FixtureSource {
+    fixture_source(vec![
+        ("pokemon", pikachu()),
+        ("pokemon-species", pikachu_species()),
+        ("move", thunder_shock()),
+        ("evolution-chain", pikachu_evolution_chain()),
+    ])
+}
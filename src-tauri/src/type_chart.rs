@@ -0,0 +1,380 @@
+// Pokemon type effectiveness (Gen 6+ chart)
+
+use crate::pokemon::PokeApiTypeResponse;
+use std::collections::HashMap;
+
+pub const POKEMON_TYPES: [&str; 18] = [
+    "normal", "fire", "water", "electric", "grass", "ice", "fighting", "poison", "ground",
+    "flying", "psychic", "bug", "rock", "ghost", "dragon", "dark", "steel", "fairy",
+];
+
+// Attacking type -> defending types that are not neutral (1x) against it
+const TYPE_CHART: &[(&str, &[(&str, f32)])] = &[
+    ("normal", &[("rock", 0.5), ("ghost", 0.0), ("steel", 0.5)]),
+    (
+        "fire",
+        &[
+            ("fire", 0.5),
+            ("water", 0.5),
+            ("grass", 2.0),
+            ("ice", 2.0),
+            ("bug", 2.0),
+            ("rock", 0.5),
+            ("dragon", 0.5),
+            ("steel", 2.0),
+        ],
+    ),
+    (
+        "water",
+        &[
+            ("fire", 2.0),
+            ("water", 0.5),
+            ("grass", 0.5),
+            ("ground", 2.0),
+            ("rock", 2.0),
+            ("dragon", 0.5),
+        ],
+    ),
+    (
+        "electric",
+        &[
+            ("water", 2.0),
+            ("electric", 0.5),
+            ("grass", 0.5),
+            ("ground", 0.0),
+            ("flying", 2.0),
+            ("dragon", 0.5),
+        ],
+    ),
+    (
+        "grass",
+        &[
+            ("fire", 0.5),
+            ("water", 2.0),
+            ("grass", 0.5),
+            ("poison", 0.5),
+            ("ground", 2.0),
+            ("flying", 0.5),
+            ("bug", 0.5),
+            ("rock", 2.0),
+            ("dragon", 0.5),
+            ("steel", 0.5),
+        ],
+    ),
+    (
+        "ice",
+        &[
+            ("fire", 0.5),
+            ("water", 0.5),
+            ("grass", 2.0),
+            ("ice", 0.5),
+            ("ground", 2.0),
+            ("flying", 2.0),
+            ("dragon", 2.0),
+            ("steel", 0.5),
+        ],
+    ),
+    (
+        "fighting",
+        &[
+            ("normal", 2.0),
+            ("ice", 2.0),
+            ("poison", 0.5),
+            ("flying", 0.5),
+            ("psychic", 0.5),
+            ("bug", 0.5),
+            ("rock", 2.0),
+            ("ghost", 0.0),
+            ("dark", 2.0),
+            ("steel", 2.0),
+            ("fairy", 0.5),
+        ],
+    ),
+    (
+        "poison",
+        &[
+            ("grass", 2.0),
+            ("poison", 0.5),
+            ("ground", 0.5),
+            ("rock", 0.5),
+            ("ghost", 0.5),
+            ("steel", 0.0),
+            ("fairy", 2.0),
+        ],
+    ),
+    (
+        "ground",
+        &[
+            ("fire", 2.0),
+            ("electric", 2.0),
+            ("grass", 0.5),
+            ("poison", 2.0),
+            ("flying", 0.0),
+            ("bug", 0.5),
+            ("rock", 2.0),
+            ("steel", 2.0),
+        ],
+    ),
+    (
+        "flying",
+        &[
+            ("electric", 0.5),
+            ("grass", 2.0),
+            ("fighting", 2.0),
+            ("bug", 2.0),
+            ("rock", 0.5),
+            ("steel", 0.5),
+        ],
+    ),
+    (
+        "psychic",
+        &[
+            ("fighting", 2.0),
+            ("poison", 2.0),
+            ("psychic", 0.5),
+            ("dark", 0.0),
+            ("steel", 0.5),
+        ],
+    ),
+    (
+        "bug",
+        &[
+            ("fire", 0.5),
+            ("grass", 2.0),
+            ("fighting", 0.5),
+            ("poison", 0.5),
+            ("flying", 0.5),
+            ("psychic", 2.0),
+            ("ghost", 0.5),
+            ("dark", 2.0),
+            ("steel", 0.5),
+            ("fairy", 0.5),
+        ],
+    ),
+    (
+        "rock",
+        &[
+            ("fire", 2.0),
+            ("ice", 2.0),
+            ("fighting", 0.5),
+            ("ground", 0.5),
+            ("flying", 2.0),
+            ("bug", 2.0),
+            ("steel", 0.5),
+        ],
+    ),
+    (
+        "ghost",
+        &[
+            ("normal", 0.0),
+            ("psychic", 2.0),
+            ("ghost", 2.0),
+            ("dark", 0.5),
+        ],
+    ),
+    ("dragon", &[("dragon", 2.0), ("steel", 0.5), ("fairy", 0.0)]),
+    (
+        "dark",
+        &[
+            ("fighting", 0.5),
+            ("psychic", 2.0),
+            ("ghost", 2.0),
+            ("dark", 0.5),
+            ("fairy", 0.5),
+        ],
+    ),
+    (
+        "steel",
+        &[
+            ("fire", 0.5),
+            ("water", 0.5),
+            ("electric", 0.5),
+            ("ice", 2.0),
+            ("rock", 2.0),
+            ("steel", 0.5),
+            ("fairy", 2.0),
+        ],
+    ),
+    (
+        "fairy",
+        &[
+            ("fire", 0.5),
+            ("fighting", 2.0),
+            ("poison", 0.5),
+            ("dragon", 2.0),
+            ("dark", 2.0),
+            ("steel", 0.5),
+        ],
+    ),
+];
+
+/// Damage multiplier of an attacking type against a single defending type
+/// Type effectiveness for every attacking type. The built-in chart is used until one
+/// has been imported, e.g. from a PokeAPI api-data dump.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeChart {
+    // Attacking type -> defending type -> multiplier, for matchups that are not 1x
+    matchups: HashMap<String, HashMap<String, f32>>,
+}
+
+impl TypeChart {
+    /// Build a chart from (attacking, defending, multiplier) rows; missing pairs are 1x
+    pub fn from_matchups(matchups: impl IntoIterator<Item = (String, String, f32)>) -> Self {
+        let mut chart = TypeChart {
+            matchups: HashMap::new(),
+        };
+        for (attacking, defending, multiplier) in matchups {
+            chart
+                .matchups
+                .entry(attacking)
+                .or_default()
+                .insert(defending, multiplier);
+        }
+        chart
+    }
+
+    pub fn from_pokeapi_responses(responses: &[PokeApiTypeResponse]) -> Self {
+        Self::from_matchups(responses.iter().flat_map(|response| {
+            let relations = &response.damage_relations;
+            [
+                (&relations.double_damage_to, 2.0),
+                (&relations.half_damage_to, 0.5),
+                (&relations.no_damage_to, 0.0),
+            ]
+            .into_iter()
+            .flat_map(move |(defenders, multiplier)| {
+                defenders.iter().map(move |defending| {
+                    (response.name.clone(), defending.name.clone(), multiplier)
+                })
+            })
+        }))
+    }
+
+    /// Every non-neutral matchup as (attacking, defending, multiplier)
+    pub fn matchups(&self) -> Vec<(&str, &str, f32)> {
+        let mut matchups: Vec<(&str, &str, f32)> = self
+            .matchups
+            .iter()
+            .flat_map(|(attacking, defenders)| {
+                defenders.iter().map(move |(defending, multiplier)| {
+                    (attacking.as_str(), defending.as_str(), *multiplier)
+                })
+            })
+            .collect();
+        matchups.sort_by(|a, b| (a.0, a.1).cmp(&(b.0, b.1)));
+        matchups
+    }
+
+    pub fn effectiveness(&self, attacking: &str, defending: &str) -> f32 {
+        self.matchups
+            .get(attacking)
+            .and_then(|defenders| defenders.get(defending))
+            .copied()
+            .unwrap_or(1.0)
+    }
+
+    /// Combined multiplier against a single- or dual-typed defender (e.g. 4x, 0.25x or 0x)
+    pub fn defensive_multiplier(&self, attacking: &str, defending_types: &[&str]) -> f32 {
+        defending_types
+            .iter()
+            .map(|defending| self.effectiveness(attacking, defending))
+            .product()
+    }
+}
+
+impl Default for TypeChart {
+    fn default() -> Self {
+        Self::from_matchups(TYPE_CHART.iter().flat_map(|(attacking, defenders)| {
+            defenders.iter().map(|(defending, multiplier)| {
+                (attacking.to_string(), defending.to_string(), *multiplier)
+            })
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chart_covers_every_type_with_valid_multipliers() {
+        assert_eq!(TYPE_CHART.len(), POKEMON_TYPES.len());
+        for (attacking, matchups) in TYPE_CHART {
+            assert!(POKEMON_TYPES.contains(attacking), "{}", attacking);
+            for (defending, multiplier) in *matchups {
+                assert!(POKEMON_TYPES.contains(defending), "{}", defending);
+                assert!(
+                    [0.0, 0.5, 2.0].contains(multiplier),
+                    "{} vs {}",
+                    attacking,
+                    defending
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn single_type_matchups() {
+        let chart = TypeChart::default();
+        assert_eq!(chart.effectiveness("fire", "grass"), 2.0);
+        assert_eq!(chart.effectiveness("water", "grass"), 0.5);
+        assert_eq!(chart.effectiveness("normal", "ghost"), 0.0);
+        assert_eq!(chart.effectiveness("dragon", "fairy"), 0.0);
+        assert_eq!(chart.effectiveness("fairy", "dragon"), 2.0);
+        assert_eq!(chart.effectiveness("normal", "fire"), 1.0);
+        assert_eq!(chart.effectiveness("shadow", "fire"), 1.0);
+    }
+
+    #[test]
+    fn dual_type_matchups_multiply() {
+        let chart = TypeChart::default();
+        assert_eq!(
+            chart.defensive_multiplier("ice", &["dragon", "flying"]),
+            4.0
+        );
+        assert_eq!(
+            chart.defensive_multiplier("fire", &["water", "dragon"]),
+            0.25
+        );
+        assert_eq!(
+            chart.defensive_multiplier("ground", &["electric", "flying"]),
+            0.0
+        );
+        assert_eq!(chart.defensive_multiplier("fire", &["grass", "water"]), 1.0);
+        assert_eq!(chart.defensive_multiplier("rock", &["bug"]), 2.0);
+    }
+
+    #[test]
+    fn charts_are_read_from_pokeapi_types() {
+        let resource = |name: &str| crate::pokemon::PokeApiResource {
+            name: name.to_string(),
+            url: String::new(),
+        };
+        let ghost = PokeApiTypeResponse {
+            id: 8,
+            name: "ghost".to_string(),
+            damage_relations: crate::pokemon::PokeApiDamageRelations {
+                double_damage_to: vec![resource("ghost"), resource("psychic")],
+                half_damage_to: vec![resource("dark")],
+                no_damage_to: vec![resource("normal")],
+            },
+            pokemon: Vec::new(),
+        };
+        let chart = TypeChart::from_pokeapi_responses(&[ghost]);
+
+        assert_eq!(chart.effectiveness("ghost", "psychic"), 2.0);
+        assert_eq!(chart.effectiveness("ghost", "normal"), 0.0);
+        assert_eq!(chart.effectiveness("ghost", "fire"), 1.0);
+        assert_eq!(chart.effectiveness("fire", "grass"), 1.0);
+        assert_eq!(chart.matchups().len(), 4);
+        assert_eq!(
+            TypeChart::from_matchups(
+                TypeChart::default()
+                    .matchups()
+                    .into_iter()
+                    .map(|(a, d, m)| (a.to_string(), d.to_string(), m))
+            ),
+            TypeChart::default()
+        );
+    }
+}
@@ -1,5 +1,6 @@
 // filepath: e:\development\Darkcaves-Dragonites\src\components\layout\Layout.tsx
-import React, { useState } from "react";
+import React, { useEffect, useState } from "react";
+import { tauriApi } from "@/services";
 import Header from "./Header";
 import Sidebar from "./Sidebar";
 import Footer from "./Footer";
@@ -11,6 +12,20 @@ interface LayoutProps {
 
 export const Layout: React.FC<LayoutProps> = ({ children }) => {
   const [sidebarOpen, setSidebarOpen] = useState(false);
+  const [databaseError, setDatabaseError] = useState<string | null>(null);
+
+  useEffect(() => {
+    tauriApi
+      .getDatabaseStatus()
+      .then((status) => {
+        if (!status.persistent) {
+          setDatabaseError(status.error ?? "Database unavailable");
+        }
+      })
+      .catch(() => {
+        // Not running under Tauri (e.g. the browser dev server)
+      });
+  }, []);
 
   const handleMenuToggle = () => {
     setSidebarOpen(!sidebarOpen);
@@ -30,6 +45,13 @@ export const Layout: React.FC<LayoutProps> = ({ children }) => {
         {/* Header */}
         <Header onMenuToggle={handleMenuToggle} sidebarOpen={sidebarOpen} />
 
+        {/* Data is not being saved */}
+        {databaseError && (
+          <div className="bg-red-100 dark:bg-red-900 text-red-800 dark:text-red-100 px-6 py-2 text-sm">
+            Your data will not be saved this session: {databaseError}
+          </div>
+        )}
+
         {/* Page Content */}
         <main className="flex-1 overflow-auto p-6">{children}</main>
 
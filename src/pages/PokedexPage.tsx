@@ -70,52 +70,47 @@ export const PokedexPage: React.FC<PokedexPageProps> = ({ className = "" }) => {
   const [initLog, setInitLog] = useState<string[]>([]);
   const [initProgress, setInitProgress] = useState<number>(0);
 
-  // Ensure database is initialized on mount
-  useEffect(() => {
-    (async () => {
-      setInitMessage("Checking Pokemon database...");
-      setInitLog([]);
-      setInitProgress(0);
-      try {
-        // Custom invoke to get progress updates
-        // We'll use a polling approach for now, since Tauri commands are not streaming
-        let done = false;
-        let lastLoaded = 0;
-        let total = 1010;
-        setInitLog((log) => [...log, "Starting initialization..."]);
-        // Call the backend and poll the cache count every second
-        const ensurePromise = tauriApi.ensurePokemonDatabaseInitialized(total);
-        while (!done) {
-          // Poll the count
-          const count = await tauriApi
-            .getCacheStats()
-            .then((s) => s.pokemonCount)
-            .catch(() => 0);
-          setInitProgress(Math.min(100, Math.round((count / total) * 100)));
-          setInitLog((log) => {
-            if (count !== lastLoaded) {
-              lastLoaded = count;
-              return [...log, `Loaded ${count} / ${total} Pokemon`];
-            }
-            return log;
-          });
-          if (count >= total) {
-            done = true;
-            break;
+  // Run a bulk load behind the progress screen, following the loader's progress events
+  const runDatabaseLoad = useCallback(async (load: () => Promise<string>) => {
+    let unlisten: (() => void) | undefined;
+    setDbReady(false);
+    setInitMessage("Checking Pokemon database...");
+    setInitLog([]);
+    setInitProgress(0);
+    try {
+      unlisten = await tauriApi.onPokemonLoadProgress(
+        ({ loaded, failed, total, eta_seconds }) => {
+          const done = loaded + failed;
+          setInitProgress(total ? Math.round((done / total) * 100) : 100);
+          // One log line per 25 Pokemon, plus the last one
+          if (done % 25 === 0 || done === total) {
+            setInitLog((log) => [
+              ...log,
+              `Loaded ${loaded} / ${total} Pokemon (${failed} failed, about ${eta_seconds}s left)`,
+            ]);
           }
-          await new Promise((res) => setTimeout(res, 1000));
         }
-        const msg = await ensurePromise;
-        setInitMessage(msg);
-        setInitLog((log) => [...log, msg]);
-      } catch (e) {
-        setInitMessage("Failed to initialize Pokemon database");
-        setInitLog((log) => [...log, "Initialization failed."]);
-      }
-      setDbReady(true);
-    })();
+      );
+      setInitLog((log) => [...log, "Starting initialization..."]);
+      const msg = await load();
+      setInitMessage(msg);
+      setInitLog((log) => [...log, msg]);
+    } catch (e) {
+      setInitMessage("Failed to initialize Pokemon database");
+      setInitLog((log) => [...log, "Initialization failed."]);
+    }
+    unlisten?.();
+    setDbReady(true);
   }, []);
 
+  // On launch only the first generation is downloaded; the rest is an explicit action
+  useEffect(() => {
+    runDatabaseLoad(() => tauriApi.ensurePokemonDatabaseInitialized());
+  }, [runDatabaseLoad]);
+
+  const handleLoadAllGenerations = () =>
+    runDatabaseLoad(() => tauriApi.initializeAllPokemonData());
+
   // Load Pokemon data with smart caching strategy
   const loadPokemon = useCallback(
     async (offset: number = 0, append: boolean = false) => {
@@ -368,6 +363,13 @@ export const PokedexPage: React.FC<PokedexPageProps> = ({ className = "" }) => {
               ></div>
             </div>
             <div className="text-sm text-gray-600 mb-2">{initProgress}%</div>
+            <Button
+              variant="secondary"
+              size="sm"
+              onClick={() => tauriApi.cancelPokemonLoad()}
+            >
+              Skip remaining downloads
+            </Button>
           </div>
           <div className="bg-gray-900 text-gray-100 rounded p-3 h-48 overflow-y-auto text-xs font-mono shadow-inner">
             {initLog.map((line, i) => (
@@ -420,6 +422,9 @@ export const PokedexPage: React.FC<PokedexPageProps> = ({ className = "" }) => {
               >
                 Clear Filters
               </Button>
+              <Button variant="outline" onClick={handleLoadAllGenerations}>
+                Download all generations
+              </Button>
             </div>
 
             {/* Results summary */}
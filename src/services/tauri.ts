@@ -1,10 +1,16 @@
 import { invoke } from "@tauri-apps/api/tauri";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 import type {
   Pokemon,
   UserPokemon,
   Trainer,
   CacheStats,
+  DatabaseStatus,
+  LoadProgress,
   PokemonListResponse,
+  Generation,
+  Pokedex,
+  DexEntry,
 } from "@/types";
 
 // Tauri command wrappers
@@ -31,6 +37,10 @@ export const tauriApi = {
     return await invoke("get_cache_stats");
   },
 
+  async getDatabaseStatus(): Promise<DatabaseStatus> {
+    return await invoke("get_database_status");
+  },
+
   async getPokemonBatch(offset: number, limit: number): Promise<Pokemon[]> {
     return await invoke("get_pokemon_batch", { offset, limit });
   },
@@ -65,9 +75,29 @@ export const tauriApi = {
     });
   },
 
-  // Initialize Pokemon data
-  async initializePokemonData(generation?: number): Promise<string> {
-    return await invoke("initialize_pokemon_data", { generation });
+  // Initialize Pokemon data for one generation, or every species in a named Pokedex
+  async initializePokemonData(
+    generation?: number,
+    pokedex?: string
+  ): Promise<string> {
+    return await invoke("initialize_pokemon_data", { generation, pokedex });
+  },
+
+  // Generation and Pokedex metadata
+  async getGenerations(): Promise<Generation[]> {
+    return await invoke("get_generations");
+  },
+
+  async getPokedexes(): Promise<Pokedex[]> {
+    return await invoke("get_pokedexes");
+  },
+
+  async getDexEntries(speciesId: number): Promise<DexEntry[]> {
+    return await invoke("get_dex_entries", { speciesId });
+  },
+
+  async refreshGenerationData(): Promise<string> {
+    return await invoke("refresh_generation_data");
   },
 
   // Stat conversion commands
@@ -143,8 +173,33 @@ export const tauriApi = {
     return await invoke("get_missing_pokemon_ids", { startId, endId });
   },
 
-  async ensurePokemonDatabaseInitialized(maxId?: number): Promise<string> {
-    return await invoke("ensure_pokemon_database_initialized", { maxId });
+  // Make sure one generation (Gen 1 by default) or Pokedex is downloaded
+  async ensurePokemonDatabaseInitialized(
+    generation?: number,
+    pokedex?: string
+  ): Promise<string> {
+    return await invoke("ensure_pokemon_database_initialized", {
+      generation,
+      pokedex,
+    });
+  },
+
+  // Download every species of every generation
+  async initializeAllPokemonData(): Promise<string> {
+    return await invoke("initialize_all_pokemon_data");
+  },
+
+  // Stop a running bulk load; what has loaded so far is kept
+  async cancelPokemonLoad(): Promise<void> {
+    return await invoke("cancel_pokemon_load");
+  },
+
+  async onPokemonLoadProgress(
+    handler: (progress: LoadProgress) => void
+  ): Promise<UnlistenFn> {
+    return await listen<LoadProgress>("pokemon-load-progress", (event) =>
+      handler(event.payload)
+    );
   },
 };
 
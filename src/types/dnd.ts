@@ -1,9 +1,13 @@
 // D&D 5e types and interfaces
 export interface DnDStatBlock {
+  size: CreatureSize;
   abilityScores: AbilityScores;
   armorClass: number;
   hitPoints: number;
+  hitDice: HitDice;
   speed: number;
+  // In pounds
+  carryingCapacity: number;
   skills: Skill[];
   resistances: DamageType[];
   vulnerabilities: DamageType[];
@@ -12,6 +16,23 @@ export interface DnDStatBlock {
   challengeRating: number;
   proficiencyBonus: number;
   savingThrows: SavingThrow[];
+  ability: string | null;
+  heldItem: string | null;
+}
+
+export type CreatureSize =
+  | "tiny"
+  | "small"
+  | "medium"
+  | "large"
+  | "huge"
+  | "gargantuan";
+
+// Hit dice in 5e notation, e.g. 12d10 + 24
+export interface HitDice {
+  count: number;
+  die: number;
+  modifier: number;
 }
 
 export interface AbilityScores {
@@ -70,14 +91,23 @@ export interface SavingThrow {
   proficient: boolean;
 }
 
-// Conversion settings
+// Conversion settings, saved as a named ConversionProfile
 export interface ConversionSettings {
+  name: string;
   levelMultiplier: number;
+  abilityScoreScale: number;
   statScaling: StatScaling;
-  useCustomRules: boolean;
-  customRules: CustomRule[];
+  damageTypes: DamageTypeMap;
+  hitPointMethod: HitPointMethod;
+}
+
+// PokeAPI type name -> damage type
+export interface DamageTypeMap {
+  mappings: Record<string, DamageType>;
 }
 
+export type HitPointMethod = "average" | "roll";
+
 export interface StatScaling {
   hpMultiplier: number;
   attackMultiplier: number;
@@ -87,15 +117,6 @@ export interface StatScaling {
   maxAbilityScore: number;
 }
 
-export interface CustomRule {
-  id: string;
-  name: string;
-  description: string;
-  condition: string; // JSON representation of condition
-  effect: string; // JSON representation of effect
-  enabled: boolean;
-}
-
 // Additional types for complete D&D creature representation
 export interface DnDStats {
   strength: number;
@@ -161,6 +161,42 @@ export interface CacheStats {
   last_updated: string;
 }
 
+// Whether data is being saved; persistent is false when the database file could not
+// be opened and the app fell back to an in-memory database
+export interface DatabaseStatus {
+  persistent: boolean;
+  error: string | null;
+}
+
+// Progress of a bulk Pokemon load, sent as the "pokemon-load-progress" event
+export interface LoadProgress {
+  loaded: number;
+  failed: number;
+  total: number;
+  eta_seconds: number;
+}
+
+export interface Generation {
+  id: number;
+  name: string;
+  main_region: string;
+  species_ids: number[];
+}
+
+export interface Pokedex {
+  id: number;
+  name: string;
+  region: string | null;
+  is_main_series: boolean;
+}
+
+// A species' number in one Pokedex
+export interface DexEntry {
+  pokedex: string;
+  entry_number: number;
+  species_id: number;
+}
+
 // Enhanced Pokemon list response with pagination
 export interface PokemonListResponse {
   pokemon: Pokemon[];